@@ -9,8 +9,8 @@
 //! elements across disparate vectors (whether different layers, buffers, etc -
 //! doesn't matter) need to be bundled together.
 //!
-//! This is NOT for references, cross-references, base addresses, or keeping
-//! track of logic within a binary. This is the wrong layer for that. I
+//! This is NOT for references, cross-references, or keeping track of logic
+//! within a binary. This is the wrong layer for that. I
 //! struggled a lot to scope this jussst right, and I started finding that I
 //! can't do too much here.
 //!
@@ -30,6 +30,15 @@
 //! basically means it knows its own index / size, which simplifies insertion
 //! a great deal.
 //!
+//! # Index size
+//!
+//! Indexes, sizes, and addresses are all `u64`, no matter the target, and so
+//! are the links in `MultiEntry::linked`. The entries themselves are still
+//! stored in a `BumpyVector`, and `AutoBumpyEntry::range()` is still a
+//! `Range<usize>`, so on 32-bit targets (including wasm32) anything that
+//! doesn't fit in a `usize` returns an error (or isn't found) instead of
+//! being truncated.
+//!
 //! # Example
 //!
 //! ```
@@ -130,17 +139,64 @@
 pub use bumpy_vector::AutoBumpyEntry;
 
 use bumpy_vector::{BumpyVector, BumpyEntry};
-use simple_error::{SimpleResult, bail};
-use std::collections::HashMap;
-use std::fmt::Debug;
+use simple_error::{SimpleError, SimpleResult, bail};
+use std::error::Error;
+use std::any::{Any, TypeId};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug};
 use std::mem;
 use std::hash::Hash;
+use std::iter::Peekable;
 use std::clone::Clone;
-use std::ops::Range;
+use std::ops::{Bound, Range, RangeBounds};
+use std::sync::Arc;
 
 #[cfg(feature = "serialize")]
 use serde::{Serialize, Deserialize};
 
+/// Convert an index, size, or address into the `usize` that `BumpyVector`
+/// and `AutoBumpyEntry` use. On a target where `usize` is smaller than 64
+/// bits, a value that doesn't fit is an error rather than being truncated.
+fn to_usize(value: u64) -> SimpleResult<usize> {
+    match usize::try_from(value) {
+        Ok(value) => Ok(value),
+        Err(_) => bail!("{} is too large for this platform", value),
+    }
+}
+
+/// Convert a range of indexes or addresses into a range of `usize`s (see
+/// `to_usize()`).
+fn to_usize_range(range: &Range<u64>) -> SimpleResult<Range<usize>> {
+    Ok(to_usize(range.start)?..to_usize(range.end)?)
+}
+
+/// Convert a `usize` from `BumpyVector` or `AutoBumpyEntry` into a `u64`.
+/// This can't lose anything, since `usize` is never bigger than 64 bits.
+fn to_u64(value: usize) -> u64 {
+    value as u64
+}
+
+/// Convert a range of `usize`s into a range of `u64`s (see `to_u64()`).
+fn to_u64_range(range: &Range<usize>) -> Range<u64> {
+    to_u64(range.start)..to_u64(range.end)
+}
+
+/// An `AutoBumpyEntry` that can be moved to a different index.
+///
+/// Since entries know their own range, operations that shift entries around
+/// (such as `MultiVector::insert_space()`) need a way to tell the entry where
+/// it went.
+pub trait MovableEntry: AutoBumpyEntry {
+    /// Move the entry so its range starts at `start`, without changing its
+    /// size.
+    ///
+    /// If the entry is in a vector with a base address, `start` is an
+    /// address.
+    fn move_to(&mut self, start: usize);
+}
+
 /// Wraps the `T` type in an object with more information.
 ///
 /// This is automatically created by `MultiVector` when inserting elements.
@@ -155,7 +211,19 @@ where
 {
     pub vector: N,
     pub data: T,
-    pub linked: Vec<(N, usize)>,
+    pub linked: Vec<(N, u64)>,
+    pub group: usize,
+
+    /// Whether the entry is pinned (see `MultiVector::pin_entry()`).
+    pub pinned: bool,
+
+    /// The entries this one has a weak link to (see
+    /// `MultiVector::link_weak()`).
+    pub weak: Vec<(N, u64)>,
+
+    /// The entry's tags, in the order they were added (see
+    /// `MultiVector::tag_entry()`).
+    pub tags: Vec<String>,
 }
 
 impl<N, T> AutoBumpyEntry for MultiEntry<N, T>
@@ -168,1159 +236,7513 @@ where
     }
 }
 
-/// The primary struct that powers the MultiVector.
+/// A single vector, along with the information we track about it.
+///
+/// This is internal; the `MultiVector` API only ever exposes the pieces.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
-pub struct MultiVector<N, T>
+struct Vector<N, T, M>
 where
     N: Hash + Eq + Debug + Clone,
     T: AutoBumpyEntry,
 {
-    // A map of bumpy_vectors, indexed by name
-    vectors: HashMap<N, BumpyVector<MultiEntry<N, T>>>,
+    // The actual entries
+    entries: Entries<N, T>,
+
+    // The options the vector was created with
+    options: VectorOptions,
+
+    // The user-defined metadata for the vector
+    meta: M,
+
+    // The vector this was derived from (if any), and the range within that
+    // vector that it came from
+    parent: Option<(N, Range<u64>)>,
+
+    // The address of the first entry, if the vector is addressed by something
+    // other than a zero-based index
+    base_address: Option<u64>,
+
+    // Whether the vector's entries are locked (see `freeze_vector()`)
+    frozen: bool,
 }
 
-impl<'a, N, T> MultiVector<N, T>
+impl<N, T, M> Vector<N, T, M>
 where
     N: Hash + Eq + Debug + Clone,
     T: AutoBumpyEntry,
 {
+    /// Convert a range of addresses (or indexes, if the vector has no base
+    /// address) into a range of indexes.
+    fn to_index_range(&self, range: Range<u64>) -> SimpleResult<Range<u64>> {
+        let base = match self.base_address {
+            Some(b) => b,
+            None => return Ok(range),
+        };
 
-    /// Create a new - empty - instance.
-    pub fn new() -> Self {
-        MultiVector {
-            vectors: HashMap::new(),
+        if range.start < base || range.end < range.start || range.end - base > self.entries.max_size() {
+            bail!("Address range {:?} is outside of the vector ({:?})", range, base..base.saturating_add(self.entries.max_size()));
         }
-    }
 
-    /// Create a vector with a given name and size.
-    ///
-    /// # Return
-    ///
-    /// Returns `Ok(())` if the vector is successfully created, or `Err(s)` with
-    /// a descriptive error message if it can't be created.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Start with no vectors
-    /// assert_eq!(0, mv.vector_count());
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 1000).unwrap();
-    ///
-    /// // Now there's one vector
-    /// assert_eq!(1, mv.vector_count());
-    /// ```
+        Ok((range.start - base)..(range.end - base))
+    }
 
-    pub fn create_vector(&mut self, name: N, max_size: usize) -> SimpleResult<()> {
-        if self.vectors.contains_key(&name) {
-            bail!("Vector with that name already exists");
+    /// Convert an index into an address (or leave it alone, if the vector has
+    /// no base address).
+    fn to_address(&self, index: u64) -> SimpleResult<u64> {
+        match self.base_address.unwrap_or(0).checked_add(index) {
+            Some(address) => Ok(address),
+            None => bail!("The address of index {} doesn't fit in a u64", index),
         }
-
-        self.vectors.insert(name, BumpyVector::new(max_size));
-
-        Ok(())
     }
 
-    /// Remove a vector with the given name.
-    ///
-    /// Vectors can only be removed if they are empty - otherwise this will
-    /// fail. The justification is, we want this to all be compatible with
-    /// undo/redo, which means removing items must be replayable. If we do two
-    /// things at once (both remove elements and the vector), the API gets
-    /// really complicated.
-    ///
-    /// # Return
-    ///
-    /// Returns a result containing either the size that the buffer was (for
-    /// ease of re-creation in an `undo()` function), or a user-consumeable
-    /// error message.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Create a vector of size 1000, then remove it
-    /// mv.create_vector("myvector", 1000).unwrap();
-    /// assert_eq!(1000, mv.destroy_vector(&"myvector").unwrap());
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 100).unwrap();
-    ///
-    /// // Populate it
-    /// mv.insert_entry(&"myvector", MyEntryType { data: 111, index: 0, size: 10 }).unwrap();
-    ///
-    /// // Fail to remove it
-    /// assert!(mv.destroy_vector(&"myvector").is_err());
-    /// ```
-    pub fn destroy_vector(&mut self, vector: &N) -> SimpleResult<usize> {
-        let v = match self.vectors.get(vector) {
-            Some(v) => v,
-            None => bail!("Vector with that name does not exist"),
-        };
+    /// Make sure an entry at the range of indexes would obey the vector's
+    /// alignment and granularity (see `VectorOptions`).
+    fn check_layout(&self, range: &Range<u64>) -> Result<(), LayoutError> {
+        if let Some(alignment) = self.options.alignment {
+            let start = match self.base_address.unwrap_or(0).checked_add(range.start) {
+                Some(start) => start,
+                None => return Err(LayoutError::AddressOverflow { index: range.start }),
+            };
 
-        if v.len() != 0 {
-            bail!("Vector is not empty");
+            if start.checked_rem(alignment) != Some(0) {
+                return Err(LayoutError::Misaligned { start, alignment });
+            }
         }
 
-        match self.vectors.remove(vector) {
-            Some(v) => Ok(v.max_size()),
-            None    => bail!("Vector with that name disappeared"),
+        if let Some(granularity) = self.options.granularity {
+            let size = range.end - range.start;
+
+            if size.checked_rem(granularity) != Some(0) {
+                return Err(LayoutError::BadGranularity { size, granularity });
+            }
         }
-    }
 
-    /// Forceably remove and return a vector, even if it's not empty.
-    ///
-    /// This is much more complex, and not recommended to use unless it's
-    /// absolutely necessary. I'd suggest wiping undo history if this is used.
-    ///
-    /// Additionally note that this might cause hanging references, where
-    /// members of a group of entries goes away. This library will handle that
-    /// as well as it can, but it may cause other problems.
-    pub fn force_destroy_vector(&mut self, vector: &N) -> Option<BumpyVector<MultiEntry<N, T>>> {
-        self.vectors.remove(vector)
+        Ok(())
     }
 
-    /// Remove entries without properly unlinking them.
-    ///
-    /// This is for internal use only.
-    fn _force_remove(&mut self, entries: Vec<(&N, usize)>) {
-        for (vector, index) in entries {
-            match self.vectors.get_mut(&vector) {
-                Some(v) => {
-                    v.remove(index);
-                },
-                None => (),
-            };
-        }
+    /// Go through the entries that overlap the range of indexes (or every
+    /// entry, if there's no range), in order, as they're needed.
+    fn iter_range(&self, range: Option<Range<u64>>) -> EntriesRange<'_, N, T> {
+        self.entries.iter_range(range)
     }
 
-    /// Insert a grouped set of entries into the `MultiVector`.
-    ///
-    /// The `entries` argument is a vector of tuples, where the first element
-    /// is the vector name and the second and onwards effectively describes a
-    /// `BumpyEntry` - `(data, index, size)`.
-    ///
-    /// Entries inserted together are "linked", which means when one is removed,
-    /// they are all removed (unless you call `unlink()` on one of them).
-    ///
-    /// Either all entries are added, or no entries are added; this cannot
-    /// leave the vector in a half-way state.
-    ///
-    /// # Return
-    ///
-    /// Returns `Ok(())` if the entries were successfully inserted. Returns a
-    /// descriptive error otherwise.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 1000).unwrap();
-    ///
-    /// // Verify there are no entries
-    /// assert_eq!(0, mv.len());
-    ///
-    /// // Populate it
-    /// mv.insert_entries(vec![
-    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
-    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
-    /// ]);
-    ///
-    /// // Now there are two entries
-    /// assert_eq!(2, mv.len());
-    ///
-    /// // Remove one entry
-    /// mv.remove_entries(&"myvector", 5).unwrap();
-    ///
-    /// // Prove it removes both
-    /// assert_eq!(0, mv.len());
-    /// ```
-    pub fn insert_entries(&mut self, entries: Vec<(&N, T)>) -> SimpleResult<()> {
-        // Get the set of references that each entry will store - the vector and
-        // location of each other. Copying the references over and over isn't
-        // the best way to implement this, probably, but also isn't entirely
-        // unreasonable.
-        let references: Vec<(N, usize)> = entries.iter().map(|(vector, entry)| {
-            // Need to copy into each reference (we could probably use a Rc<>
-            // or something if this becomes a bottleneck)
-            ((*vector).clone(), entry.range().start)
-        }).collect();
-
-        // We need a way to back out only entries that we've added; we can't
-        // just use `references` because that'll include things we haven't
-        // inserted yet (which is potentially other valid entries)
-        let mut backtrack: Vec<(&N, usize)> = Vec::new();
-
-        // Loop through each entry we're adding
-        for (vector, entry) in entries {
-            // Try and get a handle to the vector
-            let v = match self.vectors.get_mut(&vector) {
-                Some(v) => v,
-                None => {
-                    // Remove the entries we've added so far + return error
-                    self._force_remove(backtrack);
-                    bail!("Couldn't find vector: {:?}", vector);
-                }
-            };
+    /// Get all entries that overlap the range of indexes, in order.
+    fn get_range(&self, range: Range<u64>) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        self.entries.get_range(range)
+    }
+}
 
-            // Unwrap the BumpyEntry and make a new one with a MultiEntry instead
-            let entry = MultiEntry {
-                // We're forced to clone this because we reference `vector`
-                // again when adding to `backtrack`.
-                vector: vector.clone(),
+/// Where the entries with each key start.
+///
+/// Each start also has a count of how many entries start there with the key,
+/// since more than one can in a vector with overlapping entries.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+struct KeyIndex<K>
+where
+    K: Ord,
+{
+    keys: BTreeMap<K, BTreeMap<usize, usize>>,
+}
 
-                // I don't love cloning references, but we'd need to
-                // somehow redesign the linking otherwise.
-                linked: references.clone(),
-                data: entry,
-            };
+// Deriving this would require `K` to implement `Default`
+impl<K> Default for KeyIndex<K>
+where
+    K: Ord,
+{
+    fn default() -> Self {
+        KeyIndex { keys: BTreeMap::new() }
+    }
+}
 
-            // Save the index for later
-            let index = entry.range().start;
+impl<K> KeyIndex<K>
+where
+    K: Ord,
+{
+    fn add(&mut self, key: K, start: usize) {
+        *self.keys.entry(key).or_default().entry(start).or_insert(0) += 1;
+    }
 
-            // Try and insert it into the BumpyVector
-            match v.insert_auto(entry) {
-                Ok(()) => (),
-                Err(e) => {
-                    // Remove the entries we've added so far + return error
-                    self._force_remove(backtrack);
-                    bail!("Error inserting into vector: {}", e);
+    fn remove<Q>(&mut self, key: &Q, start: usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if let Some(starts) = self.keys.get_mut(key) {
+            if let Some(count) = starts.get_mut(&start) {
+                *count -= 1;
+                if *count == 0 {
+                    starts.remove(&start);
                 }
             }
 
-            // Track what's been added
-            backtrack.push((vector, index));
+            if starts.is_empty() {
+                self.keys.remove(key);
+            }
         }
-
-        Ok(())
     }
 
-    /// Insert a single entry, unlinked to others.
-    ///
-    /// This is a simple wrapper for `insert_entries()`.
-    pub fn insert_entry(&mut self, vector: &N, entry: T) -> SimpleResult<()> {
-        self.insert_entries(vec![(vector, entry)])
+    /// Get the starting index of every entry with the key, in order.
+    fn starts<Q>(&self, key: &Q) -> Vec<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.keys.get(key) {
+            Some(starts) => starts.keys().copied().collect(),
+            None => Vec::new(),
+        }
     }
+}
 
-    /// Unlink an entry from its group of entries.
-    ///
-    /// This will break the connection between an entry and its group.
-    ///
-    /// # Return
-    ///
-    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
-    /// message on failure.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 1000).unwrap();
-    ///
-    /// // Verify there are no entries
-    /// assert_eq!(0, mv.len());
-    ///
-    /// // Populate it
-    /// mv.insert_entries(vec![
-    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
-    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
-    /// ]);
-    ///
-    /// // Now there are two entries
-    /// assert_eq!(2, mv.len());
-    ///
-    /// // Unlink the entries
-    /// mv.unlink_entry(&"myvector", 5);
-    ///
-    /// // Remove one entry
-    /// mv.remove_entries(&"myvector", 5).unwrap();
-    ///
-    /// // Prove it only removed one
-    /// assert_eq!(1, mv.len());
-    /// ```
-    pub fn unlink_entry(&mut self, vector: &N, index: usize) -> SimpleResult<()> {
-        // This will be a NEW vector of references
-        let new_linked: Vec<(N, usize)> = match self.vectors.get_mut(vector) {
-            // The vector they requested does exist
-            Some(v) => match v.get_mut(index) {
-                // The index in the vector does have an entry
-                Some(e) => {
-                    // Swap out the linked entry for an empty one
-                    let original_links = mem::replace(
-                        &mut e.entry.linked,      // Replace the vectors in the unlinked entry...
-                        vec![((*vector).clone(), e.range.start)]  // ...with a reference to just itself
-                    );
-
-                    // Take the list of original links, and turn it into a list
-                    // minus the removed one
-                    original_links.into_iter().filter(|(v, i)| {
-                        // Reminder: we can't use `*i == index` here, since
-                        // `index` isn't necessarily the start.
-                        !(v == vector && *i == e.range.start)
-                    }).collect()
-                }
-                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
-            },
-            None => bail!("Couldn't find vector: {:?}", vector),
-        };
+/// A user-defined index over the entries in one vector (see
+/// `MultiVector::register_index()`).
+///
+/// Each index has its own key type, so this hides it, letting indexes with
+/// different key types be kept together; lookups pass the key as an `Any`.
+trait AnyIndex<T>: Debug + Send + Sync {
+    /// Index an entry that starts at `start`.
+    fn add(&mut self, data: &T, start: usize);
 
-        // Loop through the remaining linked entries and replace the links
-        for (vector, index) in new_linked.iter() {
-            let v = self.vectors.get_mut(vector).unwrap();
-            let e = v.get_mut(*index).unwrap();
+    /// Take an entry that starts at `start` back out of the index.
+    fn remove(&mut self, data: &T, start: usize);
 
-            // Clone is required for each link, for the same reason it is in
-            // `insert_entries()`
-            e.entry.linked = new_linked.clone();
-        }
+    /// Get the starting index of every entry with the key, in order (or
+    /// nothing, if the key is the wrong type).
+    fn starts(&self, key: &dyn Any) -> Vec<usize>;
 
-        Ok(())
-    }
+    /// Check whether an entry has the key.
+    fn has_key(&self, data: &T, key: &dyn Any) -> bool;
 
-    /// Get a single entry at the requested index.
-    ///
-    /// # Return
-    ///
-    /// On success, returns a reference to a `BumpyEntry` that wraps the data
-    /// in a `MultiEntry`. I decided to return `MultiEntry` to give easier
-    /// access to the `vector` and `references` information.
-    ///
-    /// If no element exists there, return `None`.
-    pub fn get_entry(&self, vector: &N, index: usize) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
-        self.vectors.get(vector)?.get(index)
+    /// The type of the keys.
+    fn key_type(&self) -> TypeId;
+
+    /// Make an empty index with the same key function.
+    fn empty(&self) -> Box<dyn AnyIndex<T>>;
+
+    fn clone_box(&self) -> Box<dyn AnyIndex<T>>;
+}
+
+impl<T> Clone for Box<dyn AnyIndex<T>> {
+    fn clone(&self) -> Self {
+        self.clone_box()
     }
+}
 
-    /// Get the group of entries, starting at the requested one.
-    ///
-    /// # Return
-    ///
-    /// If the entry exists, return the set of entries that were inserted
-    /// together, in the same order in which they were inserted.
-    ///
-    /// Each vector element is returned as `Some(element)`. This is to handle
-    /// the unlikely case that a referenced element has disappeared at some
-    /// point. That shouldn't be possible, but we need to handle it somehow
-    /// (the most obvious place is in deserialization).
-    ///
-    /// If the original vector or element doesn't exist, return `Err` with
-    /// a descriptive error message.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 1000).unwrap();
-    ///
-    /// // Verify there are no entries
-    /// assert_eq!(0, mv.len());
-    ///
-    /// // Populate it
-    /// mv.insert_entries(vec![
-    ///     (&"myvector", MyEntryType { data: 111, index: 0, size: 10 }),
-    ///     (&"myvector", MyEntryType { data: 222, index:10, size: 10 }),
-    /// ]);
-    ///
-    /// // Prove that we get both elements back
-    /// assert_eq!(2, mv.get_entries(&"myvector", 15).unwrap().len());
-    ///
-    /// // Verify that they are still in the `MultiVector`
-    /// assert_eq!(2, mv.len());
-    /// ```
-    pub fn get_entries(&self, vector: &N, index: usize) -> SimpleResult<Vec<Option<&BumpyEntry<MultiEntry<N, T>>>>> {
-        let linked = match self.vectors.get(vector) {
-            Some(v) => match v.get(index) {
-                Some(e) => &e.entry.linked,
-                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
-            },
-            None => bail!("Couldn't find vector: {:?}", vector),
-        };
+/// A user-defined index with a key function `F`, which gets a key of type
+/// `K` from each entry.
+struct Index<F, K>
+where
+    K: Ord,
+{
+    key: Arc<F>,
+    entries: KeyIndex<K>,
+}
 
-        let mut results: Vec<Option<&BumpyEntry<MultiEntry<N, T>>>> = Vec::new();
-        for (vector, index) in linked {
-            results.push(self.get_entry(vector, *index));
+impl<F, K> Index<F, K>
+where
+    K: Ord,
+{
+    fn new(key: Arc<F>) -> Self {
+        Index {
+            key,
+            entries: KeyIndex::default(),
         }
+    }
+}
 
-        Ok(results)
+// The key function can't be printed, and the keys might not be printable
+// either
+impl<F, K> Debug for Index<F, K>
+where
+    K: Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Index").field("keys", &self.entries.keys.len()).finish()
     }
+}
 
-    /// Remove and return all entries in a group.
-    ///
-    /// # Return
-    ///
-    /// If the entry exists, return the set of entries that were inserted
-    /// together, in the same order in which they were inserted.
-    ///
-    /// Each vector element is returned as `Some(element)`. This is to handle
-    /// the unlikely case that a referenced element has disappeared at some
-    /// point. That shouldn't be possible, but we need to handle it somehow
-    /// (the most obvious place is in deserialization).
-    ///
-    /// If the original vector or element doesn't exist, return `Err` with
-    /// a descriptive error message.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use multi_vector::{MultiVector, AutoBumpyEntry};
-    /// use std::ops::Range;
-    ///
-    /// struct MyEntryType { data: u32, index: usize, size: usize }
-    /// impl AutoBumpyEntry for MyEntryType {
-    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
-    /// }
-    ///
-    /// // Create an instance that stores u32 values
-    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
-    ///
-    /// // Create a vector of size 1000
-    /// mv.create_vector("myvector", 1000).unwrap();
-    ///
-    /// // Verify there are no entries
-    /// assert_eq!(0, mv.len());
-    ///
-    /// // Populate it
-    /// mv.insert_entries(vec![
-    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
-    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
-    /// ]);
-    ///
-    /// // Prove that we get both elements back
-    /// assert_eq!(2, mv.remove_entries(&"myvector", 15).unwrap().len());
-    ///
-    /// // Verify that they are gone
-    /// assert_eq!(0, mv.len());
-    /// ```
-    pub fn remove_entries(&mut self, vector: &N, index: usize) -> SimpleResult<Vec<Option<BumpyEntry<MultiEntry<N, T>>>>> {
-        let linked = match self.vectors.get(vector) {
-            Some(v) => match v.get(index) {
-                Some(e) => e.entry.linked.clone(),
-                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
-            },
-            None => bail!("Couldn't find vector: {:?}", vector),
-        };
+impl<T, F, K> AnyIndex<T> for Index<F, K>
+where
+    F: Fn(&T) -> K + Send + Sync + 'static,
+    K: Ord + Clone + Send + Sync + 'static,
+{
+    fn add(&mut self, data: &T, start: usize) {
+        self.entries.add((self.key)(data), start);
+    }
 
+    fn remove(&mut self, data: &T, start: usize) {
+        self.entries.remove(&(self.key)(data), start);
+    }
 
-        let mut results: Vec<Option<BumpyEntry<MultiEntry<N, T>>>> = Vec::new();
-        for (vector, index) in linked {
-            match self.vectors.get_mut(&vector) {
-                Some(v) => {
-                    results.push(v.remove(index));
-                },
-                // Bad reference (shouldn't happen)
-                None => results.push(None),
-            }
+    fn starts(&self, key: &dyn Any) -> Vec<usize> {
+        match key.downcast_ref::<K>() {
+            Some(key) => self.entries.starts(key),
+            None => Vec::new(),
         }
-
-        Ok(results)
     }
 
-    /// Returns the number of vectors in the `MultiVector`.
-    pub fn vector_count(&self) -> usize {
-        self.vectors.len()
+    fn has_key(&self, data: &T, key: &dyn Any) -> bool {
+        key.downcast_ref::<K>() == Some(&(self.key)(data))
     }
 
-    /// Returns `true` if a vector with the given name exists, false otherwise.
-    pub fn vector_exists(&self, vector: &N) -> bool {
-        self.vectors.contains_key(vector)
+    fn key_type(&self) -> TypeId {
+        TypeId::of::<K>()
     }
 
-    /// Returns the number of elements in the given vector; `None` otherwise.
-    pub fn len_vector(&self, vector: &N) -> Option<usize> {
-        let v = self.vectors.get(vector)?;
+    fn empty(&self) -> Box<dyn AnyIndex<T>> {
+        Box::new(Index::<F, K>::new(self.key.clone()))
+    }
 
-        Some(v.len())
+    fn clone_box(&self) -> Box<dyn AnyIndex<T>> {
+        Box::new(Index {
+            key: self.key.clone(),
+            entries: self.entries.clone(),
+        })
     }
+}
 
-    /// Returns the max size of the named Vector; `None` if not found.
-    pub fn max_size_vector(&self, vector: &N) -> Option<usize> {
-        let v = self.vectors.get(vector)?;
+/// The user-defined indexes, by name, with nothing in them - each vector
+/// gets its own copy of each, so that vectors created later can be indexed
+/// too.
+struct IndexKeys<T>(HashMap<String, Box<dyn AnyIndex<T>>>);
 
-        Some(v.max_size())
+// These are implemented by hand, since deriving them would require `T` to
+// implement them as well
+impl<T> Default for IndexKeys<T> {
+    fn default() -> Self {
+        IndexKeys(HashMap::new())
     }
+}
 
-    /// Returns the total number of entries across all vectors.
-    pub fn len(&self) -> usize {
-        self.vectors.iter().map(|(_, v)| v.len()).sum()
+impl<T> Clone for IndexKeys<T> {
+    fn clone(&self) -> Self {
+        IndexKeys(self.0.clone())
     }
 }
 
-/// Convert into an iterator.
+impl<T> Debug for IndexKeys<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// The entries in a single vector.
 ///
-/// Naively iterate across all entries, move them into a `Vec<_>`, and convert
-/// that vector into an iterator.
+/// A `BumpyVector` can't hold overlapping entries, so a vector that allows
+/// them keeps a stack of layers, and each entry goes into the first layer that
+/// it fits in. A vector that doesn't allow overlapping only ever has one
+/// layer.
 ///
-impl<'a, N, T> IntoIterator for &'a MultiVector<N, T>
+/// Entries are still referenced by their starting index, but in an overlapping
+/// vector that isn't always unique, so anything following a link also matches
+/// on the group.
+///
+/// This also keeps an index of the entries' tags, and the user-defined
+/// indexes. Every entry that comes or goes passes through `insert()` or
+/// `remove_from_layer()`, so that's where the indexes are kept up to date.
+///
+/// This is internal; the `MultiVector` API only ever exposes the entries.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+struct Entries<N, T>
 where
     N: Hash + Eq + Debug + Clone,
     T: AutoBumpyEntry,
 {
-    type Item = &'a BumpyEntry<MultiEntry<N, T>>;
-    type IntoIter = std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>>;
+    layers: Vec<BumpyVector<MultiEntry<N, T>>>,
+    max_size: usize,
+    overlapping: bool,
 
-    fn into_iter(self) -> std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>> {
-        let mut result: Vec<&'a BumpyEntry<MultiEntry<N, T>>> = Vec::new();
+    // Where the entries with each tag start
+    tags: KeyIndex<String>,
 
-        for (_, v) in self.vectors.iter() {
-            for e in v.into_iter() {
-                result.push(e);
-            }
+    // The user-defined indexes, by name - the key functions can't be
+    // serialized, so these are registered again after deserializing
+    #[cfg_attr(feature = "serialize", serde(skip, default = "HashMap::new"))]
+    indexes: HashMap<String, Box<dyn AnyIndex<T>>>,
+}
+
+impl<N, T> Entries<N, T>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    fn new(max_size: usize, overlapping: bool, keys: &IndexKeys<T>) -> Self {
+        Entries {
+            layers: vec![BumpyVector::new(max_size)],
+            max_size,
+            overlapping,
+            tags: KeyIndex::default(),
+            indexes: keys.0.iter().map(|(name, index)| (name.clone(), index.empty())).collect(),
         }
+    }
 
-        result.into_iter()
+    fn max_size(&self) -> u64 {
+        to_u64(self.max_size)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use pretty_assertions::assert_eq;
+    fn len(&self) -> usize {
+        self.layers.iter().map(|l| l.len()).sum()
+    }
 
-    #[cfg(feature = "serialize")]
-    use serde::{Serialize, Deserialize};
+    /// Find the layer with the best entry at `index` - one that starts there
+    /// if possible, otherwise the first one that covers it. Only entries that
+    /// match `filter` are considered.
+    fn find<F>(&self, index: u64, filter: F) -> Option<usize>
+    where
+        F: Fn(&BumpyEntry<MultiEntry<N, T>>) -> bool,
+    {
+        let index = to_usize(index).ok()?;
+        let mut covering = None;
 
-    #[derive(Debug, Clone)]
-    #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
-    struct TestEntryType {
-        data: u32,
-        index: usize,
-        size: usize,
+        for (i, layer) in self.layers.iter().enumerate() {
+            if let Some(e) = layer.get(index) {
+                if !filter(e) {
+                    continue;
+                }
+
+                if e.range.start == index {
+                    return Some(i);
+                }
+
+                if covering.is_none() {
+                    covering = Some(i);
+                }
+            }
+        }
+
+        covering
     }
 
-    impl AutoBumpyEntry for TestEntryType {
-        fn range(&self) -> Range<usize> {
-            self.index..(self.index + self.size)
+    fn get(&self, index: u64) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
+        self.layers[self.find(index, |_| true)?].get(to_usize(index).ok()?)
+    }
+
+    fn get_mut(&mut self, index: u64) -> Option<&mut BumpyEntry<MultiEntry<N, T>>> {
+        let layer = self.find(index, |_| true)?;
+
+        self.layers[layer].get_mut(to_usize(index).ok()?)
+    }
+
+    fn get_in_group(&self, index: u64, group: usize) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
+        self.layers[self.find(index, |e| e.entry.group == group)?].get(to_usize(index).ok()?)
+    }
+
+    fn get_mut_in_group(&mut self, index: u64, group: usize) -> Option<&mut BumpyEntry<MultiEntry<N, T>>> {
+        let layer = self.find(index, |e| e.entry.group == group)?;
+
+        self.layers[layer].get_mut(to_usize(index).ok()?)
+    }
+
+    /// Get the entry at `index` that has a weak link to `from`.
+    fn get_weak(&self, index: u64, from: &(N, u64)) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
+        self.layers[self.find(index, |e| e.entry.weak.contains(from))?].get(to_usize(index).ok()?)
+    }
+
+    fn get_mut_weak(&mut self, index: u64, from: &(N, u64)) -> Option<&mut BumpyEntry<MultiEntry<N, T>>> {
+        let layer = self.find(index, |e| e.entry.weak.contains(from))?;
+
+        self.layers[layer].get_mut(to_usize(index).ok()?)
+    }
+
+    /// Get every entry that overlaps `range`, in order.
+    fn get_range(&self, range: Range<u64>) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        self.iter_range(Some(range)).collect()
+    }
+
+    /// Go through the entries that overlap `range` (or every entry, if
+    /// there's no range), in order, as they're needed.
+    fn iter_range(&self, range: Option<Range<u64>>) -> EntriesRange<'_, N, T> {
+        // Nothing is past the end of the vector, so once the range is cut off
+        // there it fits in a `usize`
+        let max_size = self.max_size;
+        let clip = |i: u64| to_usize(i).map_or(max_size, |i| i.min(max_size));
+        let range = match range {
+            Some(range) => clip(range.start)..clip(range.end),
+            None => 0..max_size,
+        };
+
+        EntriesRange {
+            layers: self.layers.iter().map(|l| LayerRange::new(l, range.clone()).peekable()).collect(),
         }
     }
 
-    impl From<(u32, usize, usize)> for TestEntryType
-    {
-        fn from(o: (u32, usize, usize)) -> Self
-        {
-            TestEntryType {
-              data: o.0,
-              index: o.1,
-              size: o.2,
+    /// Get every entry that covers `index`, in layer order.
+    fn get_all(&self, index: u64) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        match to_usize(index) {
+            Ok(index) => self.layers.iter().filter_map(|l| l.get(index)).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn remove_in_group(&mut self, index: u64, group: usize) -> Option<BumpyEntry<MultiEntry<N, T>>> {
+        let layer = self.find(index, |e| e.entry.group == group)?;
+
+        self.remove_from_layer(layer, index)
+    }
+
+    fn remove_from_layer(&mut self, layer: usize, index: u64) -> Option<BumpyEntry<MultiEntry<N, T>>> {
+        let removed = self.layers[layer].remove(to_usize(index).ok()?);
+
+        // Don't hang on to empty layers (but always keep the first)
+        if layer != 0 && self.layers[layer].len() == 0 {
+            self.layers.remove(layer);
+        }
+
+        if let Some(e) = &removed {
+            for tag in e.entry.tags.iter() {
+                self.tags.remove(tag, e.range.start);
+            }
+
+            for index in self.indexes.values_mut() {
+                index.remove(&e.entry.data, e.range.start);
             }
         }
+
+        removed
     }
 
-    #[test]
-    fn test_create_and_destroy() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn insert(&mut self, entry: BumpyEntry<MultiEntry<N, T>>) -> SimpleResult<()> {
+        let start = entry.range.start;
+        let group = entry.entry.group;
 
-        // No vectors to start with
-        assert_eq!(0, mv.vector_count());
+        self.insert_into_layer(entry)?;
 
-        // Create a 1000-element vector
-        mv.create_vector("name", 1000)?;
-        assert_eq!(1, mv.vector_count());
+        // Find it again to index it, since inserting took it
+        let layer = self.find(to_u64(start), |e| e.range.start == start && e.entry.group == group);
+        let layers = &self.layers;
+        if let Some(e) = layer.and_then(|layer| layers[layer].get(start)) {
+            for tag in e.entry.tags.iter() {
+                self.tags.add(tag.clone(), start);
+            }
 
-        // Create a second vector
-        mv.create_vector("name2", 100)?;
-        assert_eq!(2, mv.vector_count());
+            for index in self.indexes.values_mut() {
+                index.add(&e.entry.data, start);
+            }
+        }
 
-        // Destroy them
-        let removed_size = mv.destroy_vector(&"name")?;
-        assert_eq!(1000, removed_size);
-        assert_eq!(1, mv.vector_count());
+        Ok(())
+    }
+
+    fn insert_into_layer(&mut self, entry: BumpyEntry<MultiEntry<N, T>>) -> SimpleResult<()> {
+        if !self.overlapping {
+            return self.layers[0].insert(entry);
+        }
+
+        // `BumpyVector::insert()` consumes the entry even when it fails, so
+        // find a layer with room for it before trying
+        let range = entry.range.start..entry.range.end.min(self.max_size);
+        let free = self.layers.iter().position(|l| LayerRange::new(l, range.clone()).next().is_none());
+
+        match free {
+            Some(layer) => self.layers[layer].insert(entry),
+            None => {
+                let mut layer = BumpyVector::new(self.max_size);
+                layer.insert(entry)?;
+                self.layers.push(layer);
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a tag to the entry in `group` at `index`, if it doesn't already
+    /// have it. Returns `None` if there's no entry there.
+    fn add_tag(&mut self, index: u64, group: usize, tag: &str) -> Option<()> {
+        let layer = self.find(index, |e| e.entry.group == group)?;
+        let e = self.layers[layer].get_mut(to_usize(index).ok()?)?;
+
+        if !e.entry.tags.iter().any(|t| t == tag) {
+            e.entry.tags.push(tag.to_string());
+
+            let start = e.range.start;
+            self.tags.add(tag.to_string(), start);
+        }
+
+        Some(())
+    }
+
+    /// Remove a tag from the entry in `group` at `index`, if it has it.
+    /// Returns `None` if there's no entry there.
+    fn remove_tag(&mut self, index: u64, group: usize, tag: &str) -> Option<()> {
+        let layer = self.find(index, |e| e.entry.group == group)?;
+        let e = self.layers[layer].get_mut(to_usize(index).ok()?)?;
+
+        if let Some(position) = e.entry.tags.iter().position(|t| t == tag) {
+            e.entry.tags.remove(position);
+
+            let start = e.range.start;
+            self.tags.remove(tag, start);
+        }
+
+        Some(())
+    }
+
+    /// Get every entry with a tag, in order.
+    fn with_tag(&self, tag: &str) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        self.tags.starts(tag).into_iter().flat_map(|start| {
+            self.get_all(to_u64(start)).into_iter().filter(move |e| {
+                e.range.start == start && e.entry.tags.iter().any(|t| t == tag)
+            })
+        }).collect()
+    }
+
+    /// Replace the data of the entry in `group` at `index`, without changing
+    /// anything else. The caller makes sure the range doesn't change. Returns
+    /// the old data, or `None` if there's no entry there.
+    fn replace_data(&mut self, index: u64, group: usize, data: T) -> Option<T> {
+        let layer = self.find(index, |e| e.entry.group == group)?;
+        let e = self.layers[layer].get_mut(to_usize(index).ok()?)?;
+        let old = mem::replace(&mut e.entry.data, data);
+
+        for i in self.indexes.values_mut() {
+            i.remove(&old, e.range.start);
+            i.add(&e.entry.data, e.range.start);
+        }
+
+        Some(old)
+    }
+
+    /// Add (or replace) a user-defined index, made from an empty copy of
+    /// `index`, and index every entry.
+    fn add_index(&mut self, name: &str, index: &dyn AnyIndex<T>) {
+        let mut index = index.empty();
+
+        for e in self.layers.iter().flat_map(|l| l.into_iter()) {
+            index.add(&e.entry.data, e.range.start);
+        }
+
+        self.indexes.insert(name.to_string(), index);
+    }
+
+    /// Throw out the user-defined indexes, and build the ones in `keys` from
+    /// scratch.
+    fn rebuild_indexes(&mut self, keys: &IndexKeys<T>) {
+        self.indexes.clear();
+
+        for (name, index) in keys.0.iter() {
+            self.add_index(name, index.as_ref());
+        }
+    }
+
+    /// Get every entry with a key in a user-defined index, in order, or `None`
+    /// if there's no such index.
+    fn with_key(&self, name: &str, key: &dyn Any) -> Option<Vec<&BumpyEntry<MultiEntry<N, T>>>> {
+        let index = self.indexes.get(name)?;
+
+        Some(index.starts(key).into_iter().flat_map(|start| {
+            self.get_all(to_u64(start)).into_iter().filter(move |e| {
+                e.range.start == start && index.has_key(&e.entry.data, key)
+            })
+        }).collect())
+    }
+
+    /// Take every entry out, in order.
+    fn into_vec(mut self) -> Vec<BumpyEntry<MultiEntry<N, T>>> {
+        let mut results = Vec::new();
+
+        for layer in self.layers.iter_mut() {
+            let starts: Vec<usize> = layer.into_iter().map(|e| e.range.start).collect();
+
+            for start in starts {
+                if let Some(e) = layer.remove(start) {
+                    results.push(e);
+                }
+            }
+        }
+
+        results.sort_by_key(|e| e.range.start);
+
+        results
+    }
+}
+
+impl<'a, N, T> IntoIterator for &'a Entries<N, T>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    type Item = &'a BumpyEntry<MultiEntry<N, T>>;
+    type IntoIter = std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>>;
+
+    fn into_iter(self) -> std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>> {
+        let mut results: Vec<&BumpyEntry<MultiEntry<N, T>>> = self.layers.iter().flat_map(|l| l.into_iter()).collect();
+
+        results.sort_by_key(|e| e.range.start);
+
+        results.into_iter()
+    }
+}
+
+/// The entries in one layer of an `Entries` that overlap a range, in order.
+///
+/// For a range that's small compared to the number of entries, it's quicker
+/// to step through the range, jumping over each entry we land in, than to
+/// look at every entry.
+enum LayerRange<'a, N, T> 
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    Step {
+        layer: &'a BumpyVector<MultiEntry<N, T>>,
+        index: usize,
+        end: usize,
+    },
+    Scan {
+        entries: std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>>,
+        range: Range<usize>,
+    },
+}
+
+impl<'a, N, T> LayerRange<'a, N, T> 
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    fn new(layer: &'a BumpyVector<MultiEntry<N, T>>, range: Range<usize>) -> Self {
+        if range.end.saturating_sub(range.start) <= layer.len() {
+            LayerRange::Step { layer, index: range.start, end: range.end }
+        } else {
+            LayerRange::Scan { entries: layer.into_iter(), range }
+        }
+    }
+}
+
+impl<'a, N, T> Iterator for LayerRange<'a, N, T> 
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    type Item = &'a BumpyEntry<MultiEntry<N, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LayerRange::Step { layer, index, end } => {
+                let layer: &'a BumpyVector<MultiEntry<N, T>> = layer;
+
+                while *index < *end {
+                    match layer.get(*index) {
+                        Some(e) => {
+                            *index = e.range.end;
+                            return Some(e);
+                        },
+                        None => *index += 1,
+                    }
+                }
+
+                None
+            },
+            LayerRange::Scan { entries, range } => {
+                entries.find(|e| e.range.start < range.end && range.start < e.range.end)
+            },
+        }
+    }
+}
+
+/// The entries in an `Entries` that overlap a range, in order (see
+/// `Entries::iter_range()`).
+///
+/// Each layer is walked separately, and the next entry is taken from
+/// whichever layer has the one that starts first.
+struct EntriesRange<'a, N, T> 
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    layers: Vec<Peekable<LayerRange<'a, N, T>>>,
+}
+
+impl<'a, N, T> Iterator for EntriesRange<'a, N, T> 
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    type Item = &'a BumpyEntry<MultiEntry<N, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Ties go to the lower layer
+        let (_, layer) = self.layers.iter_mut()
+            .enumerate()
+            .filter_map(|(i, l)| l.peek().map(|e| (e.range.start, i)))
+            .min()?;
+
+        self.layers[layer].next()
+    }
+}
+
+/// Where the entries in a vector are moving to - (old range, new range,
+/// group).
+type Moves = Vec<(Range<u64>, Range<u64>, usize)>;
+
+/// Groups nested inside another group, each with its members - (group,
+/// members).
+type NestedGroups<N> = Vec<(usize, Vec<(N, u64)>)>;
+
+/// The entries of a group that was removed (`None` for any that were
+/// missing).
+type RemovedEntryList<N, T> = Vec<Option<BumpyEntry<MultiEntry<N, T>>>>;
+
+/// The entries of a group that was removed, along with its data.
+type RemovedEntries<N, T, G> = (RemovedEntryList<N, T>, Option<G>);
+
+/// A group of entries that was removed from a `MultiVector`.
+///
+/// This is returned by operations that can remove more than one group at a
+/// time, such as `MultiVector::remove_range()`. It has everything needed to
+/// put the group back with `MultiVector::restore_groups()`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct RemovedGroup<N, T, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    pub entries: Vec<BumpyEntry<MultiEntry<N, T>>>,
+    pub data: Option<G>,
+    pub options: GroupOptions,
+}
+
+/// A vector that was destroyed along with its entries.
+///
+/// This is returned by `MultiVector::destroy_vector_cascade()`, and has
+/// everything needed to put the vector and its entries back with
+/// `MultiVector::restore_vector()`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct DestroyedVector<N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    pub name: N,
+    pub groups: Vec<RemovedGroup<N, T, G>>,
+
+    // The (empty) vector itself, with its metadata, parent, etc.
+    vector: Vector<N, T, M>,
+}
+
+/// What `MultiVector::duplicate_vector()` does with groups that have members
+/// in other vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossVectorLinks {
+    /// The copies are grouped with each other, and the members in other
+    /// vectors are left out.
+    Skip,
+
+    /// The copies are added to the original group, so they're linked to the
+    /// members in other vectors (and to the originals).
+    Link,
+}
+
+/// A summary of what `MultiVector::duplicate_vector()` did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplicateReport {
+    /// The number of entries that were copied.
+    pub entries_copied: usize,
+
+    /// The number of entries that weren't copied, because the offset map
+    /// didn't map them anywhere.
+    pub entries_skipped: usize,
+
+    /// The number of new groups that were created for the copies.
+    pub groups_created: usize,
+
+    /// The number of existing groups that the copies were added to (see
+    /// `CrossVectorLinks::Link`).
+    pub groups_linked: usize,
+
+    /// The number of group members in other vectors that were left out of the
+    /// copies' groups (see `CrossVectorLinks::Skip`).
+    pub members_skipped: usize,
+}
+
+/// Options that are set when a vector is created, with
+/// `MultiVector::create_vector_with_options()`.
+///
+/// The defaults match `MultiVector::create_vector()`.
+///
+/// The alignment and granularity are enforced everywhere entries are placed -
+/// `MultiVector::insert_entries()` fails if any entry breaks them, as do
+/// operations like `MultiVector::insert_space()` that would move an entry
+/// somewhere it isn't allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct VectorOptions {
+    /// Allow entries in the vector to overlap each other (for example, a
+    /// union, or bytes that could be either code or data).
+    ///
+    /// Overlapping entries can even start at the same index, as long as they
+    /// aren't in the same group. Anything that looks up a single entry by
+    /// index (such as `MultiVector::get_entry()`) prefers an entry that
+    /// starts at that index, and otherwise takes the first one it finds; use
+    /// `MultiVector::get_entries_at()` to see all of them.
+    pub overlapping: bool,
+
+    /// If set, every entry has to start at a multiple of this (for example,
+    /// instructions on a fixed-width architecture). If the vector has a base
+    /// address, this applies to the address rather than the index.
+    pub alignment: Option<u64>,
+
+    /// If set, the size of every entry has to be a multiple of this (for
+    /// example, a word-addressed memory).
+    pub granularity: Option<u64>,
+}
+
+/// Why an entry can't be placed in a vector with `VectorOptions::alignment`
+/// or `VectorOptions::granularity` set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The entry would start at `start` (an address, if the vector has a base
+    /// address), which isn't a multiple of `alignment`.
+    Misaligned { start: u64, alignment: u64 },
+
+    /// The entry's `size` isn't a multiple of `granularity`.
+    BadGranularity { size: u64, granularity: u64 },
+
+    /// The entry would start at `index`, but adding the vector's base address
+    /// to it doesn't fit in a `u64`.
+    AddressOverflow { index: u64 },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Misaligned { start, alignment } => write!(f, "Entry at {} isn't aligned to a multiple of {}", start, alignment),
+            LayoutError::BadGranularity { size, granularity } => write!(f, "Entry size {} isn't a multiple of {}", size, granularity),
+            LayoutError::AddressOverflow { index } => write!(f, "The address of index {} doesn't fit in a u64", index),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+impl From<LayoutError> for SimpleError {
+    fn from(e: LayoutError) -> SimpleError {
+        SimpleError::new(e.to_string())
+    }
+}
+
+/// The error returned by `MultiVector::insert_entries()` (and the other ways
+/// of inserting a group).
+///
+/// It converts into a `SimpleError`, so `?` works the same as it does
+/// everywhere else; match on it to tell layout problems and frozen vectors
+/// apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertError<N> {
+    /// An entry breaks its vector's alignment or granularity.
+    Layout(LayoutError),
+
+    /// An entry is in a frozen vector (see `MultiVector::freeze_vector()`).
+    Frozen(N),
+
+    /// Anything else (a missing vector, an overlap, etc).
+    Other(SimpleError),
+}
+
+impl<N> fmt::Display for InsertError<N>
+where
+    N: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::Layout(e) => write!(f, "{}", e),
+            InsertError::Frozen(vector) => write!(f, "Vector {:?} is frozen", vector),
+            InsertError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<N> Error for InsertError<N> where N: Debug {}
+
+impl<N> From<LayoutError> for InsertError<N> {
+    fn from(e: LayoutError) -> InsertError<N> {
+        InsertError::Layout(e)
+    }
+}
+
+impl<N> From<SimpleError> for InsertError<N> {
+    fn from(e: SimpleError) -> InsertError<N> {
+        InsertError::Other(e)
+    }
+}
+
+impl<N> From<&str> for InsertError<N> {
+    fn from(s: &str) -> InsertError<N> {
+        InsertError::Other(SimpleError::new(s))
+    }
+}
+
+impl<N> From<InsertError<N>> for SimpleError
+where
+    N: Debug,
+{
+    fn from(e: InsertError<N>) -> SimpleError {
+        match e {
+            InsertError::Layout(e) => e.into(),
+            InsertError::Frozen(_) => SimpleError::new(e.to_string()),
+            InsertError::Other(e) => e,
+        }
+    }
+}
+
+/// What `MultiVector::remove_entries()` does with the rest of a group when
+/// one of its entries is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum RemovalPolicy {
+    /// Remove the whole group.
+    #[default]
+    Cascade,
+
+    /// Unlink the entry from the group (as `MultiVector::unlink_entry()`
+    /// would), then remove just that entry. If `MultiVector::remove_range()`
+    /// covers every member of the group, the group is removed whole instead,
+    /// so `MultiVector::restore_groups()` links them again.
+    RemoveSelf,
+
+    /// Refuse to remove the entry while it's linked to anything.
+    Refuse,
+}
+
+/// Options that are set when a group is created, with
+/// `MultiVector::insert_entries_with_options()`.
+///
+/// The defaults match `MultiVector::insert_entries()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct GroupOptions {
+    /// What happens to the rest of the group when one of its entries is
+    /// removed.
+    pub removal: RemovalPolicy,
+
+    /// The group that this group is nested inside, if any (the ids are
+    /// `MultiEntry::group`). Removing a group removes the groups nested
+    /// inside it too.
+    pub parent: Option<usize>,
+}
+
+/// A group of linked entries.
+///
+/// The membership of a group is tracked by the entries themselves (in
+/// `MultiEntry::linked`); this is just the information that belongs to the
+/// group as a whole.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+struct Group<G> {
+    // The user-defined data for the group, if any was given
+    data: Option<G>,
+
+    // The options the group was created with
+    options: GroupOptions,
+
+    // The groups nested directly inside this one, so finding them doesn't
+    // mean looking at every group
+    children: BTreeSet<usize>,
+}
+
+impl<G> Group<G> {
+    fn new(data: Option<G>, options: GroupOptions) -> Self {
+        Group {
+            data,
+            options,
+            children: BTreeSet::new(),
+        }
+    }
+}
+
+/// The primary struct that powers the MultiVector.
+///
+/// `N` is the type used to name vectors, `T` is the type of the entries, and
+/// `M` is an optional piece of metadata that's stored alongside each vector
+/// (base address, endianness, etc). If no metadata is needed, `M` defaults to
+/// `()`.
+///
+/// Similarly, `G` is an optional piece of data that can be stored alongside
+/// each group of linked entries (a struct's type name, a comment, etc). It
+/// also defaults to `()`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MultiVector<N, T, M = (), G = ()>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    // A map of bumpy_vectors, indexed by name
+    vectors: HashMap<N, Vector<N, T, M>>,
+
+    // A map of groups, indexed by the id stored in each `MultiEntry`
+    groups: HashMap<usize, Group<G>>,
+
+    // The id that the next group will get
+    next_group_id: usize,
+
+    // The key functions of the user-defined indexes (see `register_index()`)
+    #[cfg_attr(feature = "serialize", serde(skip, default = "IndexKeys::default"))]
+    index_keys: IndexKeys<T>,
+}
+
+impl<'a, N, T, M, G> MultiVector<N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+
+    /// Create a new - empty - instance.
+    pub fn new() -> Self {
+        MultiVector {
+            vectors: HashMap::new(),
+            groups: HashMap::new(),
+            next_group_id: 0,
+            index_keys: IndexKeys::default(),
+        }
+    }
+
+    /// Create a vector with a given name and size.
+    ///
+    /// The vector's metadata is set to `M::default()`; use
+    /// `create_vector_with()` to set it explicitly.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the vector is successfully created, or `Err(s)` with
+    /// a descriptive error message if it can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Start with no vectors
+    /// assert_eq!(0, mv.vector_count());
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Now there's one vector
+    /// assert_eq!(1, mv.vector_count());
+    /// ```
+
+    pub fn create_vector(&mut self, name: N, max_size: u64) -> SimpleResult<()>
+    where
+        M: Default,
+    {
+        self.create_vector_with(name, max_size, M::default())
+    }
+
+    /// Create a vector with a given name, size, and metadata.
+    ///
+    /// The metadata can be anything at all - a base address, endianness, the
+    /// file the vector was loaded from, etc. It's stored alongside the vector,
+    /// and goes away when the vector does.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the vector is successfully created, or `Err(s)` with
+    /// a descriptive error message if it can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Each vector stores a base address
+    /// struct MyMeta { base: usize }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType, MyMeta> = MultiVector::new();
+    ///
+    /// // Create a vector with some metadata
+    /// mv.create_vector_with("myvector", 1000, MyMeta { base: 0x1000 }).unwrap();
+    /// assert_eq!(0x1000, mv.vector_meta(&"myvector").unwrap().base);
+    ///
+    /// // Change the metadata
+    /// mv.vector_meta_mut(&"myvector").unwrap().base = 0x2000;
+    /// assert_eq!(0x2000, mv.vector_meta(&"myvector").unwrap().base);
+    /// ```
+    pub fn create_vector_with(&mut self, name: N, max_size: u64, meta: M) -> SimpleResult<()> {
+        self.create_vector_with_options(name, max_size, meta, VectorOptions::default())
+    }
+
+    /// Create a new vector with the given metadata and options.
+    ///
+    /// The options (see `VectorOptions`) change how the vector behaves, and
+    /// can't be changed once it's created.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the vector is successfully created, or `Err(s)` with
+    /// a descriptive error message if it can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, VectorOptions};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector where entries can overlap
+    /// mv.create_vector_with_options("myvector", 100, (), VectorOptions { overlapping: true, ..Default::default() }).unwrap();
+    ///
+    /// // A union of a u32 and a u16
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 1, index: 0, size: 4 }).unwrap();
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 2, index: 0, size: 2 }).unwrap();
+    ///
+    /// // Both of them cover index 1
+    /// assert_eq!(2, mv.get_entries_at(&"myvector", 1).len());
+    /// ```
+    pub fn create_vector_with_options(&mut self, name: N, max_size: u64, meta: M, options: VectorOptions) -> SimpleResult<()> {
+        if self.vectors.contains_key(&name) {
+            bail!("Vector with that name already exists");
+        }
+
+        if options.alignment == Some(0) || options.granularity == Some(0) {
+            bail!("Alignment and granularity can't be zero");
+        }
+
+        let max_size = to_usize(max_size)?;
+
+        self.vectors.insert(name, Vector {
+            entries: Entries::new(max_size, options.overlapping, &self.index_keys),
+            options,
+            meta,
+            parent: None,
+            base_address: None,
+            frozen: false,
+        });
+
+        Ok(())
+    }
+
+    /// Create a vector that was derived from a range of another vector.
+    ///
+    /// This is for buffers that have a creator-created relationship - a
+    /// section extracted from an ELF file, a file extracted from a TAR
+    /// archive, etc. The `origin` is the range within the `parent` vector that
+    /// the new vector came from.
+    ///
+    /// The vector's metadata is set to `M::default()`; use
+    /// `create_child_vector_with()` to set it explicitly.
+    ///
+    /// A parent vector can't be destroyed while it has children.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the vector is successfully created, or `Err(s)` with
+    /// a descriptive error message if it can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a file, and a section that was extracted from it
+    /// mv.create_vector("file", 1000).unwrap();
+    /// mv.create_child_vector(&"file", "section", 100, 200..300).unwrap();
+    ///
+    /// // Check the relationship
+    /// assert_eq!(Some((&"file", 200..300)), mv.parent_of(&"section"));
+    /// assert_eq!(vec![&"section"], mv.children_of(&"file"));
+    ///
+    /// // The file can't be destroyed until the section is
+    /// assert!(mv.destroy_vector(&"file").is_err());
+    /// assert_eq!(100, mv.destroy_vector(&"section").unwrap());
+    /// assert_eq!(1000, mv.destroy_vector(&"file").unwrap());
+    /// ```
+    pub fn create_child_vector(&mut self, parent: &N, name: N, max_size: u64, origin: Range<u64>) -> SimpleResult<()>
+    where
+        M: Default,
+    {
+        self.create_child_vector_with(parent, name, max_size, origin, M::default())
+    }
+
+    /// Create a vector that was derived from a range of another vector, with
+    /// the given metadata.
+    ///
+    /// See `create_child_vector()` for details.
+    pub fn create_child_vector_with(&mut self, parent: &N, name: N, max_size: u64, origin: Range<u64>, meta: M) -> SimpleResult<()> {
+        let parent_size = match self.vectors.get(parent) {
+            Some(v) => v.entries.max_size(),
+            None => bail!("Couldn't find parent vector: {:?}", parent),
+        };
+
+        if origin.start >= origin.end {
+            bail!("Origin range {:?} is empty", origin);
+        }
+
+        if origin.end > parent_size {
+            bail!("Origin range {:?} is outside of the parent vector {:?}", origin, parent);
+        }
+
+        self.create_vector_with(name.clone(), max_size, meta)?;
+
+        // This can't fail, we just created it
+        if let Some(v) = self.vectors.get_mut(&name) {
+            v.parent = Some((parent.clone(), origin));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a vector with the given name.
+    ///
+    /// Vectors can only be removed if they are empty - otherwise this will
+    /// fail. The justification is, we want this to all be compatible with
+    /// undo/redo, which means removing items must be replayable. If we do two
+    /// things at once (both remove elements and the vector), the API gets
+    /// really complicated.
+    ///
+    /// For the same reason, vectors that have child vectors (see
+    /// `create_child_vector()`) can't be removed until the children are.
+    ///
+    /// # Return
+    ///
+    /// Returns a result containing either the size that the buffer was (for
+    /// ease of re-creation in an `undo()` function), or a user-consumeable
+    /// error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector of size 1000, then remove it
+    /// mv.create_vector("myvector", 1000).unwrap();
+    /// assert_eq!(1000, mv.destroy_vector(&"myvector").unwrap());
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // Populate it
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 111, index: 0, size: 10 }).unwrap();
+    ///
+    /// // Fail to remove it
+    /// assert!(mv.destroy_vector(&"myvector").is_err());
+    /// ```
+    pub fn destroy_vector(&mut self, vector: &N) -> SimpleResult<u64> {
+        let (max_size, _meta) = self.destroy_vector_with(vector)?;
+
+        Ok(max_size)
+    }
+
+    /// Remove a vector with the given name, and return its metadata.
+    ///
+    /// This is identical to `destroy_vector()`, except that the metadata is
+    /// returned alongside the size, so the vector can be re-created with
+    /// `create_vector_with()`.
+    pub fn destroy_vector_with(&mut self, vector: &N) -> SimpleResult<(u64, M)> {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Vector with that name does not exist"),
+        };
+
+        if v.entries.len() != 0 {
+            bail!("Vector is not empty");
+        }
+
+        if v.frozen {
+            bail!("Vector {:?} is frozen", vector);
+        }
+
+        if !self.children_of(vector).is_empty() {
+            bail!("Vector has child vectors");
+        }
+
+        match self.vectors.remove(vector) {
+            Some(v) => Ok((v.entries.max_size(), v.meta)),
+            None    => bail!("Vector with that name disappeared"),
+        }
+    }
+
+    /// Remove a vector, along with every group that has an entry in it.
+    ///
+    /// Unlike `destroy_vector()`, the vector doesn't need to be empty. Every
+    /// group with at least one entry in the vector is removed - including
+    /// members in other vectors, so no links are left dangling - and then the
+    /// vector itself is removed.
+    ///
+    /// Vectors with child vectors still can't be removed.
+    ///
+    /// # Return
+    ///
+    /// Returns everything that was removed, as a single value that can be
+    /// passed to `restore_vector()` to undo the whole thing. Returns `Err` with
+    /// a descriptive error message if the vector can't be removed; in that
+    /// case, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 100).unwrap();
+    /// mv.create_vector("vector2", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 111, index: 0, size: 10 }),
+    ///     (&"vector2", MyEntryType { data: 222, index: 0, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Destroy the first vector, taking the linked entry with it
+    /// let destroyed = mv.destroy_vector_cascade(&"vector1").unwrap();
+    /// assert_eq!(1, mv.vector_count());
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Undo
+    /// mv.restore_vector(destroyed).unwrap();
+    /// assert_eq!(2, mv.vector_count());
+    /// assert_eq!(2, mv.len());
+    /// ```
+    pub fn destroy_vector_cascade(&mut self, vector: &N) -> SimpleResult<DestroyedVector<N, T, M, G>> {
+        let max_size = match self.vectors.get(vector) {
+            Some(v) => v.entries.max_size(),
+            None => bail!("Vector with that name does not exist"),
+        };
+
+        self._check_vector_not_frozen(vector)?;
+
+        if !self.children_of(vector).is_empty() {
+            bail!("Vector has child vectors");
+        }
+
+        let groups = self.remove_range(vector, 0..max_size)?;
+
+        match self.vectors.remove(vector) {
+            Some(v) => Ok(DestroyedVector {
+                name: vector.clone(),
+                groups,
+                vector: v,
+            }),
+            None => bail!("Vector with that name disappeared"),
+        }
+    }
+
+    /// Put back a vector that was removed by `destroy_vector_cascade()`.
+    ///
+    /// The vector is re-created with the same size, metadata, parent, and
+    /// base address, then all of the groups that were removed with it are
+    /// restored.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success. If the vector's name is in use, its
+    /// parent is gone, or any of the groups can't be restored, returns `Err`
+    /// with a descriptive error message and nothing is changed (though the
+    /// `DestroyedVector` is consumed).
+    pub fn restore_vector(&mut self, destroyed: DestroyedVector<N, T, M, G>) -> SimpleResult<()> {
+        if self.vectors.contains_key(&destroyed.name) {
+            bail!("Vector with that name already exists");
+        }
+
+        if let Some((parent, _)) = &destroyed.vector.parent {
+            if !self.vectors.contains_key(parent) {
+                bail!("Couldn't find parent vector: {:?}", parent);
+            }
+        }
+
+        // The indexes might have changed since it was destroyed
+        let mut vector = destroyed.vector;
+        vector.entries.rebuild_indexes(&self.index_keys);
+        self.vectors.insert(destroyed.name.clone(), vector);
+
+        if let Err(e) = self.restore_groups(destroyed.groups) {
+            self.vectors.remove(&destroyed.name);
+            bail!("Couldn't restore vector: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Copy every entry in a vector into a new vector.
+    ///
+    /// This is for analyzing something with the same layout as something
+    /// that's already been analyzed (two versions of the same firmware, for
+    /// example). The new vector is created with the same size, metadata, and
+    /// base address as `source` (but no parent).
+    ///
+    /// `offset_map` maps the starting index of each entry in `source` to the
+    /// starting index of its copy in the new vector, or to `None` to leave it
+    /// out. Use `Some` to copy everything to the same place.
+    ///
+    /// Groups are recreated among the copies, including the group data. For
+    /// groups that also have members in other vectors, `cross_vector` decides
+    /// whether those members are left out of the new group, or whether the
+    /// copies join the original group instead.
+    ///
+    /// # Return
+    ///
+    /// Returns a `DuplicateReport` describing what was done, or `Err` with a
+    /// descriptive error message if the source doesn't exist, the new name is
+    /// in use, or the mapped entries wouldn't fit in the new vector. On
+    /// failure, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, MovableEntry, CrossVectorLinks};
+    /// use std::ops::Range;
+    ///
+    /// #[derive(Clone)]
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    /// impl MovableEntry for MyEntryType {
+    ///     fn move_to(&mut self, start: usize) { self.index = start; }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("firmware1", 100).unwrap();
+    /// mv.insert_entries(vec![
+    ///     (&"firmware1", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"firmware1", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // The second version has everything 4 bytes later
+    /// let report = mv.duplicate_vector(&"firmware1", "firmware2", |i| Some(i + 4), CrossVectorLinks::Skip).unwrap();
+    /// assert_eq!(2, report.entries_copied);
+    /// assert_eq!(1, report.groups_created);
+    ///
+    /// // The copies are grouped together
+    /// assert_eq!(2, mv.get_entries(&"firmware2", 14).unwrap().len());
+    /// ```
+    pub fn duplicate_vector<F>(&mut self, source: &N, new_name: N, offset_map: F, cross_vector: CrossVectorLinks) -> SimpleResult<DuplicateReport>
+    where
+        T: MovableEntry + Clone,
+        M: Clone,
+        G: Clone,
+        F: Fn(u64) -> Option<u64>,
+    {
+        if self.vectors.contains_key(&new_name) {
+            bail!("Vector with that name already exists");
+        }
+
+        let src = match self.vectors.get(source) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", source),
+        };
+
+        let max_size = src.entries.max_size();
+        let overlapping = src.options.overlapping;
+        let mut report = DuplicateReport::default();
+
+        // Work out where each entry is going - old start => new range
+        let mut moves: HashMap<u64, Range<u64>> = HashMap::new();
+        for e in src.entries.into_iter() {
+            let range = to_u64_range(&e.range);
+
+            match offset_map(range.start) {
+                Some(start) => {
+                    let end = match start.checked_add(range.end - range.start) {
+                        Some(end) => end,
+                        None => bail!("Copied entry at {} would end past the largest possible index", start),
+                    };
+
+                    moves.insert(range.start, start..end);
+                },
+                None => report.entries_skipped += 1,
+            }
+        }
+
+        // Make sure they'll all fit
+        let mut new_ranges: Vec<&Range<u64>> = moves.values().collect();
+        new_ranges.sort_by_key(|r| r.start);
+        let mut last_end = 0;
+        for range in new_ranges {
+            if !overlapping && range.start < last_end {
+                bail!("Copied entries would overlap at index {}", range.start);
+            }
+
+            if range.end > max_size {
+                bail!("Copied entry at {:?} would be outside of the vector", range);
+            }
+
+            src.check_layout(range)?;
+
+            last_end = range.end;
+        }
+
+        // Make copies of everything, one group at a time - (group, original
+        // links, copies)
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut groups = Vec::new();
+        for e in src.entries.into_iter() {
+            if !seen.insert(e.entry.group) {
+                continue;
+            }
+
+            let mut copies: Vec<BumpyEntry<T>> = Vec::new();
+            for (vector, index) in e.entry.linked.iter() {
+                if vector != source {
+                    continue;
+                }
+
+                if let (Some(range), Some(original)) = (moves.get(index), src.entries.get_in_group(*index, e.entry.group)) {
+                    let mut data = original.entry.data.clone();
+                    data.move_to(to_usize(src.to_address(range.start)?)?);
+
+                    copies.push(BumpyEntry { entry: data, range: to_usize_range(range)? });
+                }
+            }
+
+            // Two copies in the same group can't start at the same index
+            let starts: HashSet<usize> = copies.iter().map(|copy| copy.range.start).collect();
+            if starts.len() != copies.len() {
+                bail!("Copied entries in the same group would start at the same index");
+            }
+
+            // Linking the copies changes every member of the original group
+            let cross_vector_members = e.entry.linked.iter().any(|(vector, _)| vector != source);
+            if cross_vector == CrossVectorLinks::Link && cross_vector_members && !copies.is_empty() {
+                self._check_not_frozen(&e.entry.linked)?;
+            }
+
+            groups.push((e.entry.group, e.entry.linked.clone(), copies));
+        }
+
+        // Create the vector
+        self.vectors.insert(new_name.clone(), Vector {
+            entries: Entries::new(to_usize(max_size)?, overlapping, &self.index_keys),
+            options: src.options.clone(),
+            meta: src.meta.clone(),
+            parent: None,
+            base_address: src.base_address,
+            frozen: false,
+        });
+
+        for (group, linked, copies) in groups {
+            if copies.is_empty() {
+                continue;
+            }
+
+            report.entries_copied += copies.len();
+            let cross_vector_members = linked.iter().filter(|(vector, _)| vector != source).count();
+
+            if cross_vector == CrossVectorLinks::Link && cross_vector_members > 0 {
+                // Add the copies to the original group
+                let mut new_linked = linked.clone();
+                for copy in copies.iter() {
+                    new_linked.push((new_name.clone(), to_u64(copy.range.start)));
+                }
+
+                if let Some(v) = self.vectors.get_mut(&new_name) {
+                    for copy in copies {
+                        // This can't fail, since we validated the layout above
+                        v.entries.insert(BumpyEntry {
+                            entry: MultiEntry {
+                                vector: new_name.clone(),
+                                data: copy.entry,
+                                linked: new_linked.clone(),
+                                group,
+                                pinned: false,
+                                weak: Vec::new(),
+                                tags: Vec::new(),
+                            },
+                            range: copy.range,
+                        })?;
+                    }
+                }
+
+                self._set_links(&linked, &new_linked, group);
+
+                report.groups_linked += 1;
+            } else {
+                // Make a new group out of the copies
+                let data = self.groups.get(&group).and_then(|g| g.data.clone());
+                // The copy isn't nested in anything, the original's parent
+                // isn't its parent
+                let options = GroupOptions { parent: None, ..self._group_options(group) };
+                self._insert_entries(copies.into_iter().map(|copy| (&new_name, copy.entry)).collect(), data, options)?;
+
+                report.members_skipped += cross_vector_members;
+                report.groups_created += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Forceably remove and return a vector, even if it's not empty.
+    ///
+    /// This is much more complex, and not recommended to use unless it's
+    /// absolutely necessary. I'd suggest wiping undo history if this is used.
+    ///
+    /// Additionally note that this might cause hanging references, where
+    /// members of a group of entries goes away. This library will handle that
+    /// as well as it can, but it may cause other problems.
+    ///
+    /// Any child vectors of the destroyed vector lose their parent.
+    ///
+    /// # Return
+    ///
+    /// Returns the entries that were in the vector, in order, or `None` if the
+    /// vector doesn't exist.
+    pub fn force_destroy_vector(&mut self, vector: &N) -> Option<Vec<BumpyEntry<MultiEntry<N, T>>>> {
+        let v = self.vectors.remove(vector)?;
+
+        // Orphan the children
+        for child in self.vectors.values_mut() {
+            if let Some((parent, _)) = &child.parent {
+                if parent == vector {
+                    child.parent = None;
+                }
+            }
+        }
+
+        // Any group that lived entirely in the destroyed vector is gone now
+        for e in v.entries.into_iter() {
+            if e.entry.linked.iter().all(|(linked_vector, _)| linked_vector == vector) {
+                self._drop_group(e.entry.group);
+            }
+
+            self._prune_weak(e);
+        }
+
+        Some(v.entries.into_vec())
+    }
+
+    /// Remove entries without properly unlinking them.
+    ///
+    /// This is for internal use only.
+    fn _force_remove(&mut self, entries: Vec<(&N, u64)>, group: usize) {
+        for (vector, index) in entries {
+            match self.vectors.get_mut(&vector) {
+                Some(v) => {
+                    v.entries.remove_in_group(index, group);
+                },
+                None => (),
+            };
+        }
+    }
+
+    /// Remove a group, and wrap it in a `RemovedGroup`.
+    ///
+    /// This is for internal use only.
+    fn _remove_group(&mut self, vector: &N, index: u64, group: usize) -> SimpleResult<RemovedGroup<N, T, G>> {
+        let options = self._group_options(group);
+        let (entries, data) = self._remove_entries(vector, index, Some(group), false)?;
+
+        Ok(RemovedGroup {
+            entries: entries.into_iter().flatten().collect(),
+            data,
+            options,
+        })
+    }
+
+    /// Unlink an entry from its group and remove it by itself, and wrap it in
+    /// a `RemovedGroup` (with the options of the group it came from).
+    ///
+    /// This is for internal use only.
+    fn _remove_self(&mut self, vector: &N, index: u64, group: usize) -> SimpleResult<RemovedGroup<N, T, G>> {
+        let options = self._group_options(group);
+        let group = self._unlink_entry(vector, index, Some(group))?;
+        let (entries, data) = self._remove_entries(vector, index, Some(group), false)?;
+
+        Ok(RemovedGroup {
+            entries: entries.into_iter().flatten().collect(),
+            data,
+            options,
+        })
+    }
+
+    /// Get a copy of a group's options (or the defaults, if the group is
+    /// missing).
+    ///
+    /// This is for internal use only.
+    fn _group_options(&self, group: usize) -> GroupOptions {
+        match self.groups.get(&group) {
+            Some(g) => g.options.clone(),
+            None => GroupOptions::default(),
+        }
+    }
+
+    /// Remove a group's information. Any groups nested inside it move up to
+    /// its parent, so a group never refers to a parent that's gone.
+    ///
+    /// This is for internal use only.
+    fn _drop_group(&mut self, group: usize) -> Option<Group<G>> {
+        let removed = self.groups.remove(&group)?;
+
+        if let Some(g) = removed.options.parent.and_then(|p| self.groups.get_mut(&p)) {
+            g.children.remove(&group);
+        }
+
+        for child in removed.children.iter() {
+            self._set_parent(*child, removed.options.parent);
+        }
+
+        Some(removed)
+    }
+
+    /// Start tracking a new group, nested inside its parent (if it has one).
+    ///
+    /// This is for internal use only.
+    fn _add_group(&mut self, group: usize, data: Option<G>, options: GroupOptions) {
+        if let Some(g) = options.parent.and_then(|p| self.groups.get_mut(&p)) {
+            g.children.insert(group);
+        }
+
+        self.groups.insert(group, Group::new(data, options));
+    }
+
+    /// Move a group to a new parent (or to the top level), keeping the
+    /// parents' lists of children up to date.
+    ///
+    /// This is for internal use only.
+    fn _set_parent(&mut self, group: usize, parent: Option<usize>) {
+        let old_parent = match self.groups.get_mut(&group) {
+            Some(g) => mem::replace(&mut g.options.parent, parent),
+            None => return,
+        };
+
+        if let Some(g) = old_parent.and_then(|p| self.groups.get_mut(&p)) {
+            g.children.remove(&group);
+        }
+
+        if let Some(g) = parent.and_then(|p| self.groups.get_mut(&p)) {
+            g.children.insert(group);
+        }
+    }
+
+    /// Get the ids of the groups nested directly inside a group, in order.
+    ///
+    /// This is for internal use only.
+    fn _child_groups(&self, group: usize) -> Vec<usize> {
+        match self.groups.get(&group) {
+            Some(g) => g.children.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the id of a group followed by the ids of every group nested inside
+    /// it, depth-first.
+    ///
+    /// This is for internal use only.
+    fn _group_tree(&self, group: usize) -> Vec<usize> {
+        let mut tree: Vec<usize> = Vec::new();
+        let mut pending = vec![group];
+
+        while let Some(g) = pending.pop() {
+            tree.push(g);
+
+            // Reversed, so the first child comes off the stack first
+            pending.extend(self._child_groups(g).into_iter().rev());
+        }
+
+        tree
+    }
+
+    /// Find the members of a group, given only its id.
+    ///
+    /// Groups don't know where their members are, so this naively searches
+    /// every vector.
+    ///
+    /// This is for internal use only.
+    fn _group_members(&self, group: usize) -> Vec<(N, u64)> {
+        for v in self.vectors.values() {
+            for e in &v.entries {
+                if e.entry.group == group {
+                    return e.entry.linked.clone();
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Get every group nested (at any depth) inside `group`, depth-first,
+    /// along with its members.
+    ///
+    /// Like `_group_members()`, this has to search every vector, but it only
+    /// does it once for all of the groups - and not at all if `group` has
+    /// nothing nested inside it.
+    ///
+    /// This is for internal use only.
+    fn _descendant_members(&self, group: usize) -> NestedGroups<N> {
+        let descendants: Vec<usize> = self._group_tree(group).into_iter().skip(1).collect();
+        if descendants.is_empty() {
+            return Vec::new();
+        }
+
+        let mut members: HashMap<usize, Vec<(N, u64)>> = descendants.iter().map(|g| (*g, Vec::new())).collect();
+        let mut remaining = descendants.len();
+        'search: for v in self.vectors.values() {
+            for e in &v.entries {
+                if let Some(m) = members.get_mut(&e.entry.group) {
+                    if m.is_empty() {
+                        *m = e.entry.linked.clone();
+
+                        remaining -= 1;
+                        if remaining == 0 {
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        descendants.into_iter().map(|g| {
+            let m = members.remove(&g).unwrap_or_default();
+            (g, m)
+        }).collect()
+    }
+
+    /// Work out where every entry in a vector would go if it was relocated
+    /// (see `_relocate()`), and make sure the new layout is valid.
+    ///
+    /// Entries in `skip` (by starting index and group) are left out, for when
+    /// they'll be removed first. Nothing is changed. Returns the moves, as
+    /// (old range, new range, group), sorted by their new start.
+    ///
+    /// This is for internal use only.
+    fn _plan_relocation<F>(&self, vector: &N, new_size: u64, f: &F, skip: &HashSet<(u64, usize)>) -> SimpleResult<Moves>
+    where
+        F: Fn(u64) -> u64,
+    {
+        // Moving entries changes the links to them, wherever those are
+        self._check_links_not_frozen(vector)?;
+
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        // Work out where everything is going
+        let mut moves: Moves = v.entries.into_iter().filter(|e| {
+            !skip.contains(&(to_u64(e.range.start), e.entry.group))
+        }).map(|e| {
+            let range = to_u64_range(&e.range);
+            let start = f(range.start);
+
+            (range.clone(), start..(start + range.end - range.start), e.entry.group)
+        }).collect();
+        moves.sort_by_key(|(_, new_range, _)| new_range.start);
+
+        let mut last_end = 0;
+        for (_, new_range, _) in moves.iter() {
+            if !v.options.overlapping && new_range.start < last_end {
+                bail!("Entries in vector {:?} would overlap at index {}", vector, new_range.start);
+            }
+
+            if new_range.end > new_size {
+                bail!("Entry at {:?} would be outside of vector {:?}", new_range, vector);
+            }
+
+            v.check_layout(new_range)?;
+
+            // Each entry is told its new address, so that has to fit too
+            to_usize(v.to_address(new_range.start)?)?;
+
+            last_end = new_range.end;
+        }
+
+        Ok(moves)
+    }
+
+    /// Work out which entries in `vector` `remove_range()` would remove, by
+    /// starting index and group.
+    ///
+    /// This is for internal use only.
+    fn _range_removals(&self, vector: &N, range: Range<u64>) -> HashSet<(u64, usize)> {
+        let mut removals: HashSet<(u64, usize)> = HashSet::new();
+
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => return removals,
+        };
+        let in_range: Vec<(u64, usize)> = v.get_range(range.clone()).iter().map(|e| (to_u64(e.range.start), e.entry.group)).collect();
+
+        for e in v.get_range(range) {
+            let group = e.entry.group;
+
+            if self._removes_whole_group(vector, &e.entry, &in_range) {
+                let tree = std::iter::once((group, e.entry.linked.clone())).chain(self._descendant_members(group));
+                for (g, members) in tree {
+                    for (linked_vector, linked_index) in members {
+                        if linked_vector == *vector {
+                            removals.insert((linked_index, g));
+                        }
+                    }
+                }
+            } else {
+                removals.insert((to_u64(e.range.start), group));
+            }
+        }
+
+        removals
+    }
+
+    /// Work out whether `remove_range()` removes an entry's whole group (and
+    /// the groups nested inside it), or just the entry. `in_range` is every
+    /// entry in `vector` that overlaps the range, by starting index and group.
+    ///
+    /// This is for internal use only.
+    fn _removes_whole_group(&self, vector: &N, entry: &MultiEntry<N, T>, in_range: &[(u64, usize)]) -> bool {
+        let group = entry.group;
+
+        // A group that removes only the entry itself still goes entirely
+        // once its last member does
+        self._group_options(group).removal != RemovalPolicy::RemoveSelf || entry.linked.iter().all(|(linked_vector, linked_index)| {
+            linked_vector == vector && in_range.contains(&(*linked_index, group))
+        })
+    }
+
+    /// Move every entry in a vector, and change its size.
+    ///
+    /// `f` maps each entry's current starting index to its new starting index.
+    /// The new layout is validated before anything is changed. Links to the
+    /// moved entries (from any vector) are updated to match.
+    ///
+    /// This is for internal use only.
+    fn _relocate<F>(&mut self, vector: &N, new_size: u64, f: F) -> SimpleResult<()>
+    where
+        T: MovableEntry,
+        F: Fn(u64) -> u64,
+    {
+        // Make sure the new layout is valid before touching anything
+        let moves = self._plan_relocation(vector, new_size, &f, &HashSet::new())?;
+
+        let v = match self.vectors.get_mut(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        // Move everything into a new set of entries
+        let mut entries = Entries::new(to_usize(new_size)?, v.options.overlapping, &self.index_keys);
+        for (old_range, new_range, group) in moves.iter() {
+            if let Some(mut e) = v.entries.remove_in_group(old_range.start, *group) {
+                e.entry.data.move_to(to_usize(v.to_address(new_range.start)?)?);
+                e.range = to_usize_range(new_range)?;
+
+                // This can't fail, since we validated the layout above
+                entries.insert(e)?;
+            }
+        }
+        v.entries = entries;
+
+        // Fix up links to anything that moved
+        let moved: HashMap<u64, u64> = moves.into_iter().filter(|(old_range, new_range, _)| {
+            old_range.start != new_range.start
+        }).map(|(old_range, new_range, _)| (old_range.start, new_range.start)).collect();
+        self._fix_links(vector, &moved);
+
+        Ok(())
+    }
+
+    /// Move the origins of a vector's children.
+    ///
+    /// `f` maps each origin's current starting index to its new one.
+    ///
+    /// This is for internal use only.
+    fn _move_child_origins<F>(&mut self, vector: &N, f: F)
+    where
+        F: Fn(u64) -> u64,
+    {
+        for child in self.vectors.values_mut() {
+            if let Some((parent, origin)) = &mut child.parent {
+                if parent == vector {
+                    let start = f(origin.start);
+                    *origin = start..(start + origin.end - origin.start);
+                }
+            }
+        }
+    }
+
+    /// Update every link (weak or not) that points to a moved entry in
+    /// `vector`.
+    ///
+    /// `moved` maps each moved entry's old starting index to its new one.
+    ///
+    /// This is for internal use only.
+    fn _fix_links(&mut self, vector: &N, moved: &HashMap<u64, u64>) {
+        if moved.is_empty() {
+            return;
+        }
+
+        for v in self.vectors.values_mut() {
+            let starts: Vec<(u64, usize)> = v.entries.into_iter().map(|e| (to_u64(e.range.start), e.entry.group)).collect();
+
+            for (start, group) in starts {
+                if let Some(e) = v.entries.get_mut_in_group(start, group) {
+                    for (linked_vector, index) in e.entry.linked.iter_mut().chain(e.entry.weak.iter_mut()) {
+                        if linked_vector == vector {
+                            if let Some(new_index) = moved.get(index) {
+                                *index = *new_index;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replace the links in each of the `members` (which are in `group`) with
+    /// `links`.
+    ///
+    /// This is for internal use only.
+    fn _set_links(&mut self, members: &[(N, u64)], links: &[(N, u64)], group: usize) {
+        for (vector, index) in members {
+            if let Some(e) = self.vectors.get_mut(vector).and_then(|v| v.entries.get_mut_in_group(*index, group)) {
+                e.entry.linked = links.to_vec();
+            }
+        }
+    }
+
+    /// Replace the weak link from `partner` to `from` with a link to `to`, or
+    /// just remove it if `to` is `None`.
+    ///
+    /// This is for internal use only.
+    fn _retarget_weak(&mut self, partner: &(N, u64), from: &(N, u64), to: Option<&(N, u64)>) {
+        if let Some(e) = self.vectors.get_mut(&partner.0).and_then(|v| v.entries.get_mut_weak(partner.1, from)) {
+            e.entry.weak.retain(|w| w != from);
+
+            if let Some(to) = to {
+                if !e.entry.weak.contains(to) {
+                    e.entry.weak.push(to.clone());
+                }
+            }
+        }
+    }
+
+    /// Remove the weak links that point to an entry that's been removed.
+    ///
+    /// This is for internal use only.
+    fn _prune_weak(&mut self, removed: &BumpyEntry<MultiEntry<N, T>>) {
+        let me = (removed.entry.vector.clone(), to_u64(removed.range.start));
+
+        for partner in removed.entry.weak.iter() {
+            self._retarget_weak(partner, &me, None);
+        }
+    }
+
+    /// Make sure a vector isn't frozen.
+    ///
+    /// This is for internal use only.
+    fn _check_vector_not_frozen(&self, vector: &N) -> SimpleResult<()> {
+        match self.vectors.get(vector) {
+            Some(v) if v.frozen => bail!("Vector {:?} is frozen", vector),
+            _ => Ok(()),
+        }
+    }
+
+    /// Make sure none of the `members` of a group are in a frozen vector.
+    ///
+    /// This is for internal use only.
+    fn _check_not_frozen(&self, members: &[(N, u64)]) -> SimpleResult<()> {
+        for (vector, _) in members {
+            self._check_vector_not_frozen(vector)?;
+        }
+
+        Ok(())
+    }
+
+    /// Make sure none of the `members` of a group are pinned.
+    ///
+    /// This is for internal use only.
+    fn _check_not_pinned(&self, members: &[(N, u64)], group: usize) -> SimpleResult<()> {
+        for (vector, index) in members {
+            if let Some(e) = self.vectors.get(vector).and_then(|v| v.entries.get_in_group(*index, group)) {
+                if e.entry.pinned {
+                    bail!("The entry at index {} in vector {:?} is pinned", index, vector);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make sure a vector isn't frozen, and nothing linked to its entries is
+    /// either.
+    ///
+    /// This is for internal use only.
+    fn _check_links_not_frozen(&self, vector: &N) -> SimpleResult<()> {
+        self._check_vector_not_frozen(vector)?;
+
+        if let Some(v) = self.vectors.get(vector) {
+            for e in v.entries.into_iter() {
+                self._check_not_frozen(&e.entry.linked)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a grouped set of entries into the `MultiVector`.
+    ///
+    /// The `entries` argument is a vector of tuples, where the first element
+    /// is the vector name and the second and onwards effectively describes a
+    /// `BumpyEntry` - `(data, index, size)`.
+    ///
+    /// The group will have no group data; use `insert_entries_with()` to
+    /// attach some.
+    ///
+    /// Entries inserted together are "linked", which means when one is removed,
+    /// they are all removed (unless you call `unlink()` on one of them).
+    ///
+    /// Either all entries are added, or no entries are added; this cannot
+    /// leave the vector in a half-way state.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the entries were successfully inserted. Returns a
+    /// descriptive error otherwise - `InsertError::Layout` if an entry breaks
+    /// its vector's alignment or granularity (see `VectorOptions`), or
+    /// `InsertError::Frozen` if an entry is in a frozen vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Verify there are no entries
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Populate it
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]);
+    ///
+    /// // Now there are two entries
+    /// assert_eq!(2, mv.len());
+    ///
+    /// // Remove one entry
+    /// mv.remove_entries(&"myvector", 5).unwrap();
+    ///
+    /// // Prove it removes both
+    /// assert_eq!(0, mv.len());
+    /// ```
+    pub fn insert_entries(&mut self, entries: Vec<(&N, T)>) -> Result<(), InsertError<N>> {
+        self._insert_entries(entries, None, GroupOptions::default())
+    }
+
+    /// Insert a grouped set of entries, along with data for the group.
+    ///
+    /// This is identical to `insert_entries()`, except that the `data` is
+    /// attached to the group. It can be retrieved from any member of the group
+    /// with `group_data()`, and is returned when the group is removed with
+    /// `remove_entries_with_data()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Each group stores a string (like the name of a struct)
+    /// let mut mv: MultiVector<&str, MyEntryType, (), &str> = MultiVector::new();
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Populate it
+    /// mv.insert_entries_with(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ], "my_struct").unwrap();
+    ///
+    /// // Either member can retrieve the data
+    /// assert_eq!("my_struct", *mv.group_data(&"myvector", 5).unwrap());
+    /// assert_eq!("my_struct", *mv.group_data(&"myvector", 15).unwrap());
+    ///
+    /// // Remove the group, and get the data back
+    /// let (entries, data) = mv.remove_entries_with_data(&"myvector", 5).unwrap();
+    /// assert_eq!(2, entries.len());
+    /// assert_eq!(Some("my_struct"), data);
+    /// ```
+    pub fn insert_entries_with(&mut self, entries: Vec<(&N, T)>, data: G) -> Result<(), InsertError<N>> {
+        self._insert_entries(entries, Some(data), GroupOptions::default())
+    }
+
+    /// Insert a group of entries, with optional group data and the given
+    /// options.
+    ///
+    /// The options (see `GroupOptions`) change how the group behaves, and
+    /// can't be changed once it's created. Otherwise, this is identical to
+    /// `insert_entries_with()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, GroupOptions, RemovalPolicy};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // A group where removing one entry leaves the rest alone
+    /// mv.insert_entries_with_options(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ], None, GroupOptions { removal: RemovalPolicy::RemoveSelf, ..Default::default() }).unwrap();
+    ///
+    /// assert_eq!(1, mv.remove_entries(&"myvector", 0).unwrap().len());
+    /// assert_eq!(1, mv.len());
+    /// ```
+    pub fn insert_entries_with_options(&mut self, entries: Vec<(&N, T)>, data: Option<G>, options: GroupOptions) -> Result<(), InsertError<N>> {
+        self._insert_entries(entries, data, options)
+    }
+
+    /// Insert a group of entries, with optional group data.
+    ///
+    /// This is for internal use only.
+    fn _insert_entries(&mut self, entries: Vec<(&N, T)>, data: Option<G>, options: GroupOptions) -> Result<(), InsertError<N>> {
+        // Don't create a group with no members
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        // The parent has to exist already
+        if let Some(parent) = options.parent {
+            if !self.groups.contains_key(&parent) {
+                bail!("Couldn't find parent group: {}", parent);
+            }
+        }
+
+        // All entries in the group share an id
+        let group = self.next_group_id;
+
+        // Figure out the indexes that each entry will live at - if the vector
+        // has a base address, the entry's range is an address. This also
+        // validates the vectors before we change anything.
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for (vector, entry) in entries.iter() {
+            match self.vectors.get(*vector) {
+                Some(v) => {
+                    if v.frozen {
+                        return Err(InsertError::Frozen((*vector).clone()));
+                    }
+
+                    let range = v.to_index_range(to_u64_range(&entry.range()))?;
+                    v.check_layout(&range)?;
+                    ranges.push(range);
+                },
+                None => bail!("Couldn't find vector: {:?}", vector),
+            };
+        }
+
+        // Get the set of references that each entry will store - the vector and
+        // location of each other. Copying the references over and over isn't
+        // the best way to implement this, probably, but also isn't entirely
+        // unreasonable.
+        let references: Vec<(N, u64)> = entries.iter().zip(ranges.iter()).map(|((vector, _), range)| {
+            // Need to copy into each reference (we could probably use a Rc<>
+            // or something if this becomes a bottleneck)
+            ((*vector).clone(), range.start)
+        }).collect();
+
+        // Entries are linked by their starting index, so two entries in the
+        // same group can't share one (this only comes up in vectors with
+        // overlapping entries - see `VectorOptions`)
+        let unique: HashSet<&(N, u64)> = references.iter().collect();
+        if unique.len() != references.len() {
+            bail!("Two entries in the group start at the same index");
+        }
+
+        // We need a way to back out only entries that we've added; we can't
+        // just use `references` because that'll include things we haven't
+        // inserted yet (which is potentially other valid entries)
+        let mut backtrack: Vec<(&N, u64)> = Vec::new();
+
+        // Loop through each entry we're adding
+        for ((vector, entry), range) in entries.into_iter().zip(ranges) {
+            // Try and get a handle to the vector
+            let v = match self.vectors.get_mut(&vector) {
+                Some(v) => v,
+                None => {
+                    // Remove the entries we've added so far + return error
+                    self._force_remove(backtrack, group);
+                    bail!("Couldn't find vector: {:?}", vector);
+                }
+            };
+
+            // Unwrap the BumpyEntry and make a new one with a MultiEntry instead
+            let entry = MultiEntry {
+                // We're forced to clone this because we reference `vector`
+                // again when adding to `backtrack`.
+                vector: vector.clone(),
+
+                // I don't love cloning references, but we'd need to
+                // somehow redesign the linking otherwise.
+                linked: references.clone(),
+                data: entry,
+                group,
+                pinned: false,
+                weak: Vec::new(),
+                tags: Vec::new(),
+            };
+
+            // Save the index for later
+            let index = range.start;
+
+            // Try and insert it into the BumpyVector
+            let inserted = to_usize_range(&range).and_then(|range| v.entries.insert(BumpyEntry { entry, range }));
+            match inserted {
+                Ok(()) => (),
+                Err(e) => {
+                    // Remove the entries we've added so far + return error
+                    self._force_remove(backtrack, group);
+                    bail!("Error inserting into vector: {}", e);
+                }
+            }
+
+            // Track what's been added
+            backtrack.push((vector, index));
+        }
+
+        // Only track the group once everything is inserted
+        self._add_group(group, data, options);
+        self.next_group_id += 1;
+
+        Ok(())
+    }
+
+    /// Insert a single entry, unlinked to others.
+    ///
+    /// This is a simple wrapper for `insert_entries()`.
+    pub fn insert_entry(&mut self, vector: &N, entry: T) -> Result<(), InsertError<N>> {
+        self.insert_entries(vec![(vector, entry)])
+    }
+
+    /// Unlink an entry from its group of entries.
+    ///
+    /// This will break the connection between an entry and its group. The
+    /// entry is placed in a new group by itself, with no group data; the group
+    /// data stays with the rest of the group.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message on failure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Verify there are no entries
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Populate it
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]);
+    ///
+    /// // Now there are two entries
+    /// assert_eq!(2, mv.len());
+    ///
+    /// // Unlink the entries
+    /// mv.unlink_entry(&"myvector", 5);
+    ///
+    /// // Remove one entry
+    /// mv.remove_entries(&"myvector", 5).unwrap();
+    ///
+    /// // Prove it only removed one
+    /// assert_eq!(1, mv.len());
+    /// ```
+    pub fn unlink_entry(&mut self, vector: &N, index: u64) -> SimpleResult<()> {
+        self._unlink_entry(vector, index, None)?;
+
+        Ok(())
+    }
+
+    /// Unlink an entry from its group of entries.
+    ///
+    /// If `group` is set, only an entry in that group counts as the entry at
+    /// `index` (see `Entries`).
+    ///
+    /// This is for internal use only.
+    ///
+    /// # Return
+    ///
+    /// Returns the id of the entry's new group (or its current group, if it
+    /// was already alone).
+    fn _unlink_entry(&mut self, vector: &N, index: u64, group: Option<usize>) -> SimpleResult<usize> {
+        // Unlinking changes every member of the group
+        let entry = self.vectors.get(vector).and_then(|v| match group {
+            Some(g) => v.entries.get_in_group(index, g),
+            None => v.entries.get(index),
+        });
+        if let Some(e) = entry {
+            self._check_not_frozen(&e.entry.linked)?;
+        }
+
+        // The id of the unlinked entry's new group
+        let new_group = self.next_group_id;
+
+        // This will be a NEW vector of references (and the id of the group the
+        // entry is leaving)
+        let (new_linked, old_group): (Vec<(N, u64)>, usize) = match self.vectors.get_mut(vector) {
+            // The vector they requested does exist
+            Some(v) => {
+                let entry = match group {
+                    Some(g) => v.entries.get_mut_in_group(index, g),
+                    None => v.entries.get_mut(index),
+                };
+
+                match entry {
+                    // The index in the vector does have an entry
+                    Some(e) => {
+                        // If it's already alone, there's nothing to unlink it from
+                        if e.entry.linked.len() == 1 {
+                            return Ok(e.entry.group);
+                        }
+
+                        // Move the entry into its own group
+                        let old_group = mem::replace(&mut e.entry.group, new_group);
+
+                        // Swap out the linked entry for an empty one
+                        let original_links = mem::replace(
+                            &mut e.entry.linked,      // Replace the vectors in the unlinked entry...
+                            vec![((*vector).clone(), to_u64(e.range.start))]  // ...with a reference to just itself
+                        );
+
+                        // Take the list of original links, and turn it into a list
+                        // minus the removed one
+                        let new_linked = original_links.into_iter().filter(|(v, i)| {
+                            // Reminder: we can't use `*i == index` here, since
+                            // `index` isn't necessarily the start.
+                            !(v == vector && *i == to_u64(e.range.start))
+                        }).collect();
+
+                        (new_linked, old_group)
+                    }
+                    None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+                }
+            },
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        // Loop through the remaining linked entries and replace the links
+        for (vector, index) in new_linked.iter() {
+            let v = self.vectors.get_mut(vector).unwrap();
+            let e = v.entries.get_mut_in_group(*index, old_group).unwrap();
+
+            // Clone is required for each link, for the same reason it is in
+            // `insert_entries()`
+            e.entry.linked = new_linked.clone();
+        }
+
+        self._add_group(new_group, None, GroupOptions::default());
+        self.next_group_id += 1;
+
+        Ok(new_group)
+    }
+
+    /// Split some of the entries in a group off into a new group.
+    ///
+    /// The group is the one that the entry at `index` belongs to, and
+    /// `members` are the entries that move (each one can be any index within
+    /// the entry, like everywhere else). The moved entries stay linked to each
+    /// other, but not to the rest of the group. This is like calling
+    /// `unlink_entry()` on each of them, without losing the links between
+    /// them.
+    ///
+    /// The group data stays with the original group. The new group starts out
+    /// with no data, and the same options (see `GroupOptions`) as the
+    /// original, including its parent if it's nested. Groups nested inside
+    /// the original group stay there.
+    ///
+    /// # Return
+    ///
+    /// Returns the id of the new group (see `MultiEntry::group`), or `Err()`
+    /// with a descriptive error message if any of the members isn't in the
+    /// group, or if the split would leave either group empty. On failure,
+    /// nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 333, index: 20, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // The last two weren't really part of the first
+    /// mv.split_group(&"myvector", 0, &[("myvector", 15), ("myvector", 25)]).unwrap();
+    ///
+    /// assert_eq!(1, mv.remove_entries(&"myvector", 0).unwrap().len());
+    /// assert_eq!(2, mv.remove_entries(&"myvector", 10).unwrap().len());
+    /// ```
+    pub fn split_group(&mut self, vector: &N, index: u64, members: &[(N, u64)]) -> SimpleResult<usize> {
+        let (linked, group) = match self.get_entry(vector, index) {
+            Some(e) => (e.entry.linked.clone(), e.entry.group),
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+
+        // Splitting changes every member of the group
+        self._check_not_frozen(&linked)?;
+
+        // Find where each of the moving entries starts, since that's how the
+        // links refer to them
+        let mut moving: Vec<(N, u64)> = Vec::new();
+        for (member_vector, member_index) in members {
+            let start = match self.vectors.get(member_vector).and_then(|v| v.entries.get_in_group(*member_index, group)) {
+                Some(e) => to_u64(e.range.start),
+                None => bail!("The entry at index {} in vector {:?} isn't in the same group", member_index, member_vector),
+            };
+
+            let member = (member_vector.clone(), start);
+            if moving.contains(&member) {
+                bail!("The entry at index {} in vector {:?} was given twice", start, member_vector);
+            }
+            moving.push(member);
+        }
+
+        if moving.is_empty() {
+            bail!("No entries to split off");
+        }
+        if moving.len() == linked.len() {
+            bail!("Can't split every entry off of a group");
+        }
+
+        // Keep both sets in the order they were inserted
+        let (new_linked, old_linked) = linked.into_iter().partition::<Vec<(N, u64)>, _>(|member| moving.contains(member));
+
+        let new_group = self.next_group_id;
+        for (member_vector, member_index) in new_linked.iter() {
+            if let Some(e) = self.vectors.get_mut(member_vector).and_then(|v| v.entries.get_mut_in_group(*member_index, group)) {
+                e.entry.group = new_group;
+            }
+        }
+
+        self._set_links(&new_linked, &new_linked, new_group);
+        self._set_links(&old_linked, &old_linked, group);
+
+        let options = self._group_options(group);
+        self._add_group(new_group, None, options);
+        self.next_group_id += 1;
+
+        Ok(new_group)
+    }
+
+    /// Get a single entry at the requested index.
+    ///
+    /// # Return
+    ///
+    /// On success, returns a reference to a `BumpyEntry` that wraps the data
+    /// in a `MultiEntry`. I decided to return `MultiEntry` to give easier
+    /// access to the `vector` and `references` information.
+    ///
+    /// If no element exists there, return `None`.
+    pub fn get_entry(&self, vector: &N, index: u64) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
+        self.vectors.get(vector)?.entries.get(index)
+    }
+
+    /// Get every entry that covers the requested index.
+    ///
+    /// This is mostly useful for vectors with overlapping entries (see
+    /// `VectorOptions`), where `get_entry()` only returns one of them. In
+    /// other vectors, there's at most one.
+    ///
+    /// # Return
+    ///
+    /// Returns the entries, in order of their starting index. If the vector
+    /// doesn't exist or nothing is at that index, the list is empty.
+    pub fn get_entries_at(&self, vector: &N, index: u64) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        let mut results = match self.vectors.get(vector) {
+            Some(v) => v.entries.get_all(index),
+            None => return Vec::new(),
+        };
+
+        results.sort_by_key(|e| e.range.start);
+
+        results
+    }
+
+    /// Get a single entry at the requested address.
+    ///
+    /// This is identical to `get_entry()`, except the address is converted to
+    /// an index using the vector's base address (see `set_base_address()`).
+    ///
+    /// If the vector doesn't exist, the address is outside of the vector, or
+    /// no element exists there, return `None`.
+    pub fn get_entry_at_address(&self, vector: &N, address: u64) -> Option<&BumpyEntry<MultiEntry<N, T>>> {
+        let index = self.address_to_index(vector, address).ok()?;
+
+        self.get_entry(vector, index)
+    }
+
+    /// Get all entries that overlap a range of indexes, in order.
+    ///
+    /// Entries that only partially overlap the range are included.
+    ///
+    /// If the vector doesn't exist, return `Err` with a descriptive error
+    /// message.
+    pub fn get_range(&self, vector: &N, range: Range<u64>) -> SimpleResult<Vec<&BumpyEntry<MultiEntry<N, T>>>> {
+        match self.vectors.get(vector) {
+            Some(v) => Ok(v.get_range(range)),
+            None => bail!("Couldn't find vector: {:?}", vector),
+        }
+    }
+
+    /// Get all entries that overlap a range of addresses, in order.
+    ///
+    /// This is identical to `get_range()`, except the addresses are converted
+    /// to indexes using the vector's base address (see `set_base_address()`).
+    ///
+    /// If the vector doesn't exist, or the range isn't entirely within the
+    /// vector, return `Err` with a descriptive error message.
+    pub fn get_range_at_address(&self, vector: &N, range: Range<u64>) -> SimpleResult<Vec<&BumpyEntry<MultiEntry<N, T>>>> {
+        match self.vectors.get(vector) {
+            Some(v) => Ok(v.get_range(v.to_index_range(range)?)),
+            None => bail!("Couldn't find vector: {:?}", vector),
+        }
+    }
+
+    /// Get the group of entries, starting at the requested one.
+    ///
+    /// # Return
+    ///
+    /// If the entry exists, return the set of entries that were inserted
+    /// together, in the same order in which they were inserted.
+    ///
+    /// Each vector element is returned as `Some(element)`. This is to handle
+    /// the unlikely case that a referenced element has disappeared at some
+    /// point. That shouldn't be possible, but we need to handle it somehow
+    /// (the most obvious place is in deserialization).
+    ///
+    /// If the original vector or element doesn't exist, return `Err` with
+    /// a descriptive error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Verify there are no entries
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Populate it
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index: 0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index:10, size: 10 }),
+    /// ]);
+    ///
+    /// // Prove that we get both elements back
+    /// assert_eq!(2, mv.get_entries(&"myvector", 15).unwrap().len());
+    ///
+    /// // Verify that they are still in the `MultiVector`
+    /// assert_eq!(2, mv.len());
+    /// ```
+    pub fn get_entries(&self, vector: &N, index: u64) -> SimpleResult<Vec<Option<&BumpyEntry<MultiEntry<N, T>>>>> {
+        let (linked, group) = match self.vectors.get(vector) {
+            Some(v) => match v.entries.get(index) {
+                Some(e) => (&e.entry.linked, e.entry.group),
+                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+            },
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        let mut results: Vec<Option<&BumpyEntry<MultiEntry<N, T>>>> = Vec::new();
+        for (vector, index) in linked {
+            results.push(self.vectors.get(vector).and_then(|v| v.entries.get_in_group(*index, group)));
+        }
+
+        Ok(results)
+    }
+
+    /// Add a weak link between two entries.
+    ///
+    /// A weak link says two entries are related, without making them part of
+    /// the same group - removing one doesn't remove the other. Weak links can
+    /// be followed with `get_weak_links()`, and they're removed automatically
+    /// when either entry is.
+    ///
+    /// The entries can be in the same vector or different ones, and in the
+    /// same group or different ones. Linking two entries that are already
+    /// linked does nothing.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if either entry doesn't exist, they're the same entry, or
+    /// either one is in a frozen vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // A function, and a string that it uses
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 111, index:  0, size: 10 }).unwrap();
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 222, index: 50, size: 10 }).unwrap();
+    /// mv.link_weak(&"myvector", 0, &"myvector", 50).unwrap();
+    ///
+    /// // The link can be followed from either end
+    /// assert_eq!(222, mv.get_weak_links(&"myvector", 0).unwrap()[0].entry.data.data);
+    /// assert_eq!(111, mv.get_weak_links(&"myvector", 50).unwrap()[0].entry.data.data);
+    ///
+    /// // Removing the function leaves the string alone
+    /// assert_eq!(1, mv.remove_entries(&"myvector", 0).unwrap().len());
+    /// assert_eq!(0, mv.get_weak_links(&"myvector", 50).unwrap().len());
+    /// ```
+    pub fn link_weak(&mut self, vector1: &N, index1: u64, vector2: &N, index2: u64) -> SimpleResult<()> {
+        let first = self._weak_end(vector1, index1)?;
+        let second = self._weak_end(vector2, index2)?;
+
+        if first == second {
+            bail!("Can't link an entry to itself");
+        }
+
+        for (from, to) in [(&first, &second), (&second, &first)].iter() {
+            if let Some(e) = self.vectors.get_mut(&from.0).and_then(|v| v.entries.get_mut(from.1)) {
+                if !e.entry.weak.contains(to) {
+                    e.entry.weak.push((*to).clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a weak link that was added with `link_weak()`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if either entry doesn't exist, they aren't linked, or either
+    /// one is in a frozen vector.
+    pub fn unlink_weak(&mut self, vector1: &N, index1: u64, vector2: &N, index2: u64) -> SimpleResult<()> {
+        let first = self._weak_end(vector1, index1)?;
+        let second = self._weak_end(vector2, index2)?;
+
+        match self.get_entry(vector1, index1) {
+            Some(e) if e.entry.weak.contains(&second) => (),
+            _ => bail!("The entries at {:?} and {:?} aren't linked", first, second),
+        };
+
+        self._retarget_weak(&first, &second, None);
+        self._retarget_weak(&second, &first, None);
+
+        Ok(())
+    }
+
+    /// Find the entry at one end of a weak link, and make sure it can be
+    /// changed.
+    ///
+    /// This is for internal use only.
+    fn _weak_end(&self, vector: &N, index: u64) -> SimpleResult<(N, u64)> {
+        let start = match self.get_entry(vector, index) {
+            Some(e) => to_u64(e.range.start),
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+        self._check_vector_not_frozen(vector)?;
+
+        Ok((vector.clone(), start))
+    }
+
+    /// Get every entry that has a weak link to the requested one (see
+    /// `link_weak()`).
+    ///
+    /// # Return
+    ///
+    /// Returns the linked entries, in the order they were linked. If the
+    /// vector or entry doesn't exist, returns `Err` with a descriptive error
+    /// message.
+    pub fn get_weak_links(&self, vector: &N, index: u64) -> SimpleResult<Vec<&BumpyEntry<MultiEntry<N, T>>>> {
+        let e = match self.get_entry(vector, index) {
+            Some(e) => e,
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+        let me = (vector.clone(), to_u64(e.range.start));
+
+        Ok(e.entry.weak.iter().filter_map(|(linked_vector, linked_index)| {
+            self.vectors.get(linked_vector)?.entries.get_weak(*linked_index, &me)
+        }).collect())
+    }
+
+    /// Remove and return all entries in a group.
+    ///
+    /// If the group was created with a removal policy other than
+    /// `RemovalPolicy::Cascade` (see `insert_entries_with_options()`), and the
+    /// entry is linked to others, this follows the policy instead - either
+    /// only the entry itself is removed, or nothing is.
+    ///
+    /// When the group is removed, every group nested inside it (see
+    /// `GroupOptions::parent`) is removed too. Removing an entry in a nested
+    /// group only removes that group (and the groups inside it).
+    ///
+    /// # Return
+    ///
+    /// If the entry exists, return the set of entries that were inserted
+    /// together, in the same order in which they were inserted, followed by
+    /// the entries of any nested groups that were removed.
+    ///
+    /// Each vector element is returned as `Some(element)`. This is to handle
+    /// the unlikely case that a referenced element has disappeared at some
+    /// point. That shouldn't be possible, but we need to handle it somehow
+    /// (the most obvious place is in deserialization).
+    ///
+    /// If the original vector or element doesn't exist, or any entry in the
+    /// group is pinned (see `pin_entry()`), return `Err` with a descriptive
+    /// error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// // Create an instance that stores u32 values
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // Create a vector of size 1000
+    /// mv.create_vector("myvector", 1000).unwrap();
+    ///
+    /// // Verify there are no entries
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Populate it
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]);
+    ///
+    /// // Prove that we get both elements back
+    /// assert_eq!(2, mv.remove_entries(&"myvector", 15).unwrap().len());
+    ///
+    /// // Verify that they are gone
+    /// assert_eq!(0, mv.len());
+    /// ```
+    pub fn remove_entries(&mut self, vector: &N, index: u64) -> SimpleResult<Vec<Option<BumpyEntry<MultiEntry<N, T>>>>> {
+        let (results, _data) = self.remove_entries_with_data(vector, index)?;
+
+        Ok(results)
+    }
+
+    /// Remove and return all entries in a group, along with the group data.
+    ///
+    /// This is identical to `remove_entries()`, except that the group's data
+    /// (if it had any) is returned as well, so it can be re-inserted with
+    /// `insert_entries_with()`.
+    pub fn remove_entries_with_data(&mut self, vector: &N, index: u64) -> SimpleResult<RemovedEntries<N, T, G>> {
+        self._remove_with_policy(vector, index, false)
+    }
+
+    /// Remove and return all entries in a group, even if some are pinned.
+    ///
+    /// This is identical to `remove_entries()`, except that pinned entries
+    /// (see `pin_entry()`) don't stop it. This is for when the user really
+    /// does want to get rid of something they pinned.
+    pub fn force_remove_entries(&mut self, vector: &N, index: u64) -> SimpleResult<RemovedEntryList<N, T>> {
+        let (results, _data) = self._remove_with_policy(vector, index, true)?;
+
+        Ok(results)
+    }
+
+    /// Pin an entry, so its group can't be removed.
+    ///
+    /// This is for protecting things the user defined from automated
+    /// analysis - while an entry is pinned, `remove_entries()` (along with
+    /// anything else that removes groups, like `remove_range()`) fails when it
+    /// reaches the pinned entry's group, from any member, and the error says
+    /// which entry is pinned. Use `force_remove_entries()` to remove it
+    /// anyway.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the entry doesn't exist or its vector is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Pin one of them, and the group can't be removed from either one
+    /// mv.pin_entry(&"myvector", 0).unwrap();
+    /// assert!(mv.remove_entries(&"myvector", 0).is_err());
+    /// assert!(mv.remove_entries(&"myvector", 10).is_err());
+    ///
+    /// // Unless we insist
+    /// assert_eq!(2, mv.force_remove_entries(&"myvector", 10).unwrap().len());
+    /// ```
+    pub fn pin_entry(&mut self, vector: &N, index: u64) -> SimpleResult<()> {
+        self._set_pinned(vector, index, true)
+    }
+
+    /// Unpin an entry that was pinned with `pin_entry()`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the entry doesn't exist or its vector is frozen.
+    pub fn unpin_entry(&mut self, vector: &N, index: u64) -> SimpleResult<()> {
+        self._set_pinned(vector, index, false)
+    }
+
+    /// Add a tag to an entry.
+    ///
+    /// Tags are free-form labels (like "auto", "user", or "suspicious") that
+    /// can be used to find entries quickly, with `entries_with_tag()`. Adding
+    /// a tag that the entry already has does nothing.
+    ///
+    /// Tags stay with the entry when it moves, are returned with it when it's
+    /// removed (in `MultiEntry::tags`), and are put back by
+    /// `restore_groups()`. When an entry is split, every piece has its tags;
+    /// when entries are merged, the merged entry has all of them. Copies made
+    /// by `duplicate_vector()` start out with no tags.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the entry doesn't exist or its vector is frozen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 100).unwrap();
+    /// mv.create_vector("vector2", 100).unwrap();
+    ///
+    /// mv.insert_entry(&"vector1", MyEntryType { data: 111, index:  0, size: 10 }).unwrap();
+    /// mv.insert_entry(&"vector1", MyEntryType { data: 222, index: 10, size: 10 }).unwrap();
+    /// mv.insert_entry(&"vector2", MyEntryType { data: 333, index:  0, size: 10 }).unwrap();
+    ///
+    /// mv.tag_entry(&"vector1", 15, "suspicious").unwrap();
+    /// mv.tag_entry(&"vector2", 0, "suspicious").unwrap();
+    /// assert_eq!(2, mv.entries_with_tag("suspicious").len());
+    ///
+    /// mv.untag_entry(&"vector2", 0, "suspicious").unwrap();
+    /// assert_eq!(222, mv.entries_with_tag("suspicious")[0].entry.data.data);
+    /// ```
+    pub fn tag_entry(&mut self, vector: &N, index: u64, tag: &str) -> SimpleResult<()> {
+        self._check_vector_not_frozen(vector)?;
+
+        let group = match self.get_entry(vector, index) {
+            Some(e) => e.entry.group,
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+
+        if let Some(v) = self.vectors.get_mut(vector) {
+            v.entries.add_tag(index, group, tag);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a tag from an entry, if it has it.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the entry doesn't exist or its vector is frozen.
+    pub fn untag_entry(&mut self, vector: &N, index: u64, tag: &str) -> SimpleResult<()> {
+        self._check_vector_not_frozen(vector)?;
+
+        let group = match self.get_entry(vector, index) {
+            Some(e) => e.entry.group,
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+
+        if let Some(v) = self.vectors.get_mut(vector) {
+            v.entries.remove_tag(index, group, tag);
+        }
+
+        Ok(())
+    }
+
+    /// Returns every entry with a tag (see `tag_entry()`), across all
+    /// vectors.
+    ///
+    /// The entries are grouped by vector, and are in order within each
+    /// vector. This uses an index of the tags, so it doesn't have to look at
+    /// any entries that don't have the tag.
+    pub fn entries_with_tag(&self, tag: &str) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        self.vectors.values().flat_map(|v| v.entries.with_tag(tag)).collect()
+    }
+
+    /// Register a user-defined index over the entries' data.
+    ///
+    /// `key` derives a key from an entry (a type name, a symbol name, etc),
+    /// which can be any type that's `Ord` and `Clone`; each index has its own
+    /// key type. Entries can then be found by key with `entries_with_key()`,
+    /// without looking at every entry. To leave some entries out, use an
+    /// `Option` as the key, and never look up `None`.
+    ///
+    /// Every entry is indexed right away, and the index is kept up to date as
+    /// entries are inserted, removed, replaced (`replace_entry()`), moved,
+    /// split, and merged - including in vectors that are created later. If
+    /// there's already an index with the same name, it's replaced.
+    ///
+    /// Indexes aren't serialized, since the key functions can't be. After
+    /// deserializing, register them again and they're rebuilt from the
+    /// entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { name: &'static str, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// mv.insert_entry(&"myvector", MyEntryType { name: "main",  index:  0, size: 10 }).unwrap();
+    /// mv.insert_entry(&"myvector", MyEntryType { name: "start", index: 10, size: 10 }).unwrap();
+    ///
+    /// // Index the entries by name, and by size
+    /// mv.register_index("name", |e: &MyEntryType| e.name);
+    /// mv.register_index("size", |e: &MyEntryType| e.size);
+    /// assert_eq!(10, mv.entries_with_key("name", &"start").unwrap()[0].range.start);
+    /// assert_eq!(2, mv.entries_with_key("size", &10usize).unwrap().len());
+    ///
+    /// // New entries are indexed as they're added
+    /// mv.insert_entry(&"myvector", MyEntryType { name: "start", index: 50, size: 10 }).unwrap();
+    /// assert_eq!(2, mv.entries_with_key("name", &"start").unwrap().len());
+    ///
+    /// // And removed as they're removed
+    /// mv.remove_entries(&"myvector", 0).unwrap();
+    /// assert_eq!(0, mv.entries_with_key("name", &"main").unwrap().len());
+    /// ```
+    pub fn register_index<K, F>(&mut self, name: &str, key: F)
+    where
+        K: Ord + Clone + Send + Sync + 'static,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        let index: Box<dyn AnyIndex<T>> = Box::new(Index::new(Arc::new(key)));
+
+        for v in self.vectors.values_mut() {
+            v.entries.add_index(name, index.as_ref());
+        }
+
+        self.index_keys.0.insert(name.to_string(), index);
+    }
+
+    /// Remove a user-defined index that was registered with
+    /// `register_index()`.
+    ///
+    /// # Return
+    ///
+    /// Returns `true` if the index existed.
+    pub fn unregister_index(&mut self, name: &str) -> bool {
+        for v in self.vectors.values_mut() {
+            v.entries.indexes.remove(name);
+        }
+
+        self.index_keys.0.remove(name).is_some()
+    }
+
+    /// Returns every entry with the given key in a user-defined index (see
+    /// `register_index()`), across all vectors.
+    ///
+    /// The entries are grouped by vector, and are in order within each
+    /// vector. The key has to be exactly the index's key type - for an index
+    /// with `u32` keys, look up `&5u32` rather than `&5`.
+    ///
+    /// # Return
+    ///
+    /// Returns the entries, or `Err()` with a descriptive error message if the
+    /// index hasn't been registered, or has a different key type than `K`.
+    pub fn entries_with_key<K>(&self, index: &str, key: &K) -> SimpleResult<Vec<&BumpyEntry<MultiEntry<N, T>>>>
+    where
+        K: Ord + 'static,
+    {
+        match self.index_keys.0.get(index) {
+            Some(i) if i.key_type() == TypeId::of::<K>() => (),
+            Some(_) => bail!("Index {} has a different key type", index),
+            None => bail!("Couldn't find index: {}", index),
+        };
+
+        Ok(self.vectors.values().flat_map(|v| v.entries.with_key(index, key).unwrap_or_default()).collect())
+    }
+
+    /// Search every entry in every vector.
+    ///
+    /// `predicate` is given each entry (along with its vector and links, in a
+    /// `MultiEntry`), and the ones it returns `true` for are returned, along
+    /// with their ranges. Nothing is collected up front: the entries are
+    /// looked up, and the predicate called, as the results are used.
+    ///
+    /// The entries are grouped by vector, and are in order within each
+    /// vector. To search only some vectors, or part of a vector, see
+    /// `find_in()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 111, index:  0, size:  4 }).unwrap();
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 222, index: 10, size: 20 }).unwrap();
+    /// mv.insert_entry(&"myvector", MyEntryType { data: 333, index: 50, size: 12 }).unwrap();
+    ///
+    /// // Find everything longer than 10
+    /// let found: Vec<usize> = mv.find(|e| e.data.size > 10).map(|e| e.range.start).collect();
+    /// assert_eq!(vec![10, 50], found);
+    /// ```
+    pub fn find<'s, F>(&'s self, predicate: F) -> impl Iterator<Item = &'s BumpyEntry<MultiEntry<N, T>>> + 's
+    where
+        F: Fn(&MultiEntry<N, T>) -> bool + 's,
+    {
+        self._search(None, None).filter(move |e| predicate(&e.entry))
+    }
+
+    /// Search the entries in some of the vectors, like `find()`.
+    ///
+    /// Only the entries in `vectors` (in that order - any that don't exist are
+    /// skipped) are searched. If `range` is set, only the entries that overlap
+    /// it are.
+    pub fn find_in<'s, F>(&'s self, vectors: &[N], range: Option<Range<u64>>, predicate: F) -> impl Iterator<Item = &'s BumpyEntry<MultiEntry<N, T>>> + 's
+    where
+        F: Fn(&MultiEntry<N, T>) -> bool + 's,
+    {
+        self._search(Some(vectors), range).filter(move |e| predicate(&e.entry))
+    }
+
+    /// Search every group.
+    ///
+    /// `predicate` is given the entries in each group (in the order they were
+    /// inserted), and the groups it returns `true` for are returned. Like
+    /// `find()`, the results are computed as they're used, and each group is
+    /// only looked at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 100).unwrap();
+    /// mv.create_vector("vector2", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"vector1", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]).unwrap();
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 333, index: 50, size: 10 }),
+    ///     (&"vector2", MyEntryType { data: 444, index:  0, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Find the groups that span more than one vector
+    /// let found: Vec<_> = mv.find_groups(|group| {
+    ///     group.iter().any(|e| e.entry.vector != group[0].entry.vector)
+    /// }).collect();
+    ///
+    /// assert_eq!(1, found.len());
+    /// assert_eq!(333, found[0][0].entry.data.data);
+    /// ```
+    pub fn find_groups<'s, F>(&'s self, predicate: F) -> impl Iterator<Item = Vec<&'s BumpyEntry<MultiEntry<N, T>>>> + 's
+    where
+        F: Fn(&[&BumpyEntry<MultiEntry<N, T>>]) -> bool + 's,
+    {
+        self._search_groups(None, None, predicate)
+    }
+
+    /// Search the groups with entries in some of the vectors, like
+    /// `find_groups()`.
+    ///
+    /// A group is searched if any of its entries is in one of `vectors` (and
+    /// overlaps `range`, if it's set); the predicate is still given all of
+    /// its entries.
+    pub fn find_groups_in<'s, F>(&'s self, vectors: &[N], range: Option<Range<u64>>, predicate: F) -> impl Iterator<Item = Vec<&'s BumpyEntry<MultiEntry<N, T>>>> + 's
+    where
+        F: Fn(&[&BumpyEntry<MultiEntry<N, T>>]) -> bool + 's,
+    {
+        self._search_groups(Some(vectors), range, predicate)
+    }
+
+    /// Start building a query over the entries.
+    ///
+    /// Each filter on the `Query` narrows down the results; with no filters,
+    /// every entry is returned. The query is run when it's iterated over.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 0x1000).unwrap();
+    /// mv.create_vector("vector2", 0x1000).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 111, index: 0x000, size: 0x10 }),
+    ///     (&"vector2", MyEntryType { data: 222, index: 0x000, size: 0x10 }),
+    /// ]).unwrap();
+    /// mv.insert_entry(&"vector1", MyEntryType { data: 333, index: 0x010, size: 0x10 }).unwrap();
+    /// mv.insert_entry(&"vector1", MyEntryType { data: 444, index: 0x200, size: 0x10 }).unwrap();
+    ///
+    /// // Linked entries near the start of vector1 with odd data
+    /// let found: Vec<u32> = mv.query()
+    ///     .vectors(&["vector1"])
+    ///     .range(0..0x100)
+    ///     .group_size(2..)
+    ///     .data(|e| e.data % 2 == 1)
+    ///     .into_iter()
+    ///     .map(|e| e.entry.data.data)
+    ///     .collect();
+    ///
+    /// assert_eq!(vec![111], found);
+    /// ```
+    pub fn query(&self) -> Query<'_, N, T, M, G> {
+        Query {
+            multi_vector: self,
+            vectors: None,
+            range: None,
+            group_size: (Bound::Unbounded, Bound::Unbounded),
+            data: Vec::new(),
+        }
+    }
+
+    /// Go through the entries in `vectors` (or every vector) that overlap
+    /// `range` (or every entry), as they're needed.
+    ///
+    /// This is for internal use only.
+    fn _search<'s>(&'s self, vectors: Option<&[N]>, range: Option<Range<u64>>) -> impl Iterator<Item = &'s BumpyEntry<MultiEntry<N, T>>> + 's {
+        // Only the vectors are picked up front, their entries are looked up
+        // as the search goes
+        let selected: Vec<&Vector<N, T, M>> = match vectors {
+            Some(names) => names.iter().filter_map(|name| self.vectors.get(name)).collect(),
+            None => self.vectors.values().collect(),
+        };
+
+        selected.into_iter().flat_map(move |v| v.iter_range(range.clone()))
+    }
+
+    /// Go through the groups with entries found by `_search()`, and
+    /// return the ones that match `predicate`.
+    ///
+    /// This is for internal use only.
+    fn _search_groups<'s, F>(&'s self, vectors: Option<&[N]>, range: Option<Range<u64>>, predicate: F) -> impl Iterator<Item = Vec<&'s BumpyEntry<MultiEntry<N, T>>>> + 's
+    where
+        F: Fn(&[&BumpyEntry<MultiEntry<N, T>>]) -> bool + 's,
+    {
+        let mut seen: HashSet<usize> = HashSet::new();
+
+        self._search(vectors, range).filter_map(move |e| {
+            if !seen.insert(e.entry.group) {
+                return None;
+            }
+
+            let members = self._linked_entries(&e.entry.linked, e.entry.group);
+            if predicate(&members) {
+                Some(members)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Replace an entry's data, keeping its links, group, tags, and so on.
+    ///
+    /// The new data has to cover exactly the same range as the old data.
+    ///
+    /// # Return
+    ///
+    /// Returns the old data, or `Err()` with a descriptive error message if
+    /// the entry doesn't exist, the range doesn't match, or the vector is
+    /// frozen. On failure, nothing is changed.
+    pub fn replace_entry(&mut self, vector: &N, index: u64, entry: T) -> SimpleResult<T> {
+        let (start, group) = match self.vectors.get(vector) {
+            Some(v) => match v.entries.get(index) {
+                Some(e) => {
+                    if v.to_index_range(to_u64_range(&entry.range()))? != to_u64_range(&e.range) {
+                        bail!("The new entry's range ({:?}) doesn't match the old entry's range", entry.range());
+                    }
+
+                    (to_u64(e.range.start), e.entry.group)
+                },
+                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+            },
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+        self._check_vector_not_frozen(vector)?;
+
+        match self.vectors.get_mut(vector).and_then(|v| v.entries.replace_data(start, group, entry)) {
+            Some(old) => Ok(old),
+
+            // We just found it, so this shouldn't happen
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        }
+    }
+
+    /// Set or clear an entry's pin.
+    ///
+    /// This is for internal use only.
+    fn _set_pinned(&mut self, vector: &N, index: u64, pinned: bool) -> SimpleResult<()> {
+        self._check_vector_not_frozen(vector)?;
+
+        match self.vectors.get_mut(vector) {
+            Some(v) => match v.entries.get_mut(index) {
+                Some(e) => e.entry.pinned = pinned,
+                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+            },
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        Ok(())
+    }
+
+    /// Remove an entry, and whatever else its group's removal policy (see
+    /// `RemovalPolicy`) says to.
+    ///
+    /// This is for internal use only.
+    fn _remove_with_policy(&mut self, vector: &N, index: u64, force: bool) -> SimpleResult<RemovedEntries<N, T, G>> {
+        let (start, members, group, pinned) = match self.get_entry(vector, index) {
+            Some(e) => (e.range.start, e.entry.linked.len(), e.entry.group, e.entry.pinned),
+
+            // Let the normal removal report the error
+            None => return self._remove_entries(vector, index, None, force),
+        };
+
+        if members == 1 {
+            return self._remove_with_descendants(vector, index, group, force);
+        }
+
+        match self._group_options(group).removal {
+            RemovalPolicy::Cascade => self._remove_with_descendants(vector, index, group, force),
+            RemovalPolicy::RemoveSelf => {
+                if pinned && !force {
+                    bail!("The entry at index {} in vector {:?} is pinned", start, vector);
+                }
+
+                let group = self._unlink_entry(vector, index, Some(group))?;
+                self._remove_entries(vector, index, Some(group), force)
+            },
+            RemovalPolicy::Refuse => bail!("The entry at index {} in vector {:?} is linked to other entries, and its group can't be removed", start, vector),
+        }
+    }
+
+    /// Remove all entries in a group, and every group nested inside it.
+    ///
+    /// The nested groups' entries are appended to the results, and their data
+    /// is dropped.
+    ///
+    /// This is for internal use only.
+    fn _remove_with_descendants(&mut self, vector: &N, index: u64, group: usize, force: bool) -> SimpleResult<RemovedEntries<N, T, G>> {
+        let descendants = self._descendants_to_remove(group, force)?;
+        let (mut results, data) = self._remove_entries(vector, index, Some(group), force)?;
+
+        for (descendant, members) in descendants {
+            match members.first() {
+                Some((linked_vector, linked_index)) => {
+                    let (entries, _data) = self._remove_entries(linked_vector, *linked_index, Some(descendant), true)?;
+                    results.extend(entries);
+                },
+                None => {
+                    self._drop_group(descendant);
+                },
+            }
+        }
+
+        Ok((results, data))
+    }
+
+    /// Find every group nested (at any depth) inside `group`, with its
+    /// members (see `_descendant_members()`), and make sure they can all be
+    /// removed.
+    ///
+    /// This is for internal use only.
+    fn _descendants_to_remove(&self, group: usize, force: bool) -> SimpleResult<NestedGroups<N>> {
+        let descendants = self._descendant_members(group);
+
+        for (descendant, members) in descendants.iter() {
+            self._check_not_frozen(members)?;
+            if !force {
+                self._check_not_pinned(members, *descendant)?;
+            }
+        }
+
+        Ok(descendants)
+    }
+
+    /// Remove all entries in a group, and the group's data.
+    ///
+    /// If `group` is set, only an entry in that group counts as the entry at
+    /// `index` (see `Entries`). If `force` is set, pinned entries are removed
+    /// too.
+    ///
+    /// This is for internal use only.
+    fn _remove_entries(&mut self, vector: &N, index: u64, group: Option<usize>, force: bool) -> SimpleResult<RemovedEntries<N, T, G>> {
+        let (linked, group) = match self.vectors.get(vector) {
+            Some(v) => match group.map_or_else(|| v.entries.get(index), |g| v.entries.get_in_group(index, g)) {
+                Some(e) => (e.entry.linked.clone(), e.entry.group),
+                None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+            },
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        // Every member has to be removable, not just the one we started from
+        self._check_not_frozen(&linked)?;
+        if !force {
+            self._check_not_pinned(&linked, group)?;
+        }
+
+        let mut results: Vec<Option<BumpyEntry<MultiEntry<N, T>>>> = Vec::new();
+        for (vector, index) in linked {
+            match self.vectors.get_mut(&vector) {
+                Some(v) => {
+                    results.push(v.entries.remove_in_group(index, group));
+                },
+                // Bad reference (shouldn't happen)
+                None => results.push(None),
+            }
+        }
+
+        // Weak links don't take anything else with them, they just go away
+        for e in results.iter().flatten() {
+            self._prune_weak(e);
+        }
+
+        let data = match self._drop_group(group) {
+            Some(g) => g.data,
+            None => None,
+        };
+
+        Ok((results, data))
+    }
+
+    /// Returns a reference to the data of the group that the entry at the
+    /// given index belongs to.
+    ///
+    /// Returns `None` if the entry doesn't exist, or if its group has no data.
+    pub fn group_data(&self, vector: &N, index: u64) -> Option<&G> {
+        let group = self.get_entry(vector, index)?.entry.group;
+
+        self.groups.get(&group)?.data.as_ref()
+    }
+
+    /// Returns a mutable reference to the data of the group that the entry at
+    /// the given index belongs to.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(None)` if the group has no data (use `set_group_data()` to
+    /// add some), or `Err` with a descriptive error message if the entry
+    /// doesn't exist or any of the group's entries are in a frozen vector.
+    pub fn group_data_mut(&mut self, vector: &N, index: u64) -> SimpleResult<Option<&mut G>> {
+        let group = match self.get_entry(vector, index) {
+            Some(e) => {
+                self._check_not_frozen(&e.entry.linked)?;
+                e.entry.group
+            },
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+
+        Ok(self.groups.get_mut(&group).and_then(|g| g.data.as_mut()))
+    }
+
+    /// Replace the data of the group that the entry at the given index belongs
+    /// to.
+    ///
+    /// # Return
+    ///
+    /// Returns the previous group data (if any), or `Err` with a descriptive
+    /// error message if the entry doesn't exist.
+    pub fn set_group_data(&mut self, vector: &N, index: u64, data: Option<G>) -> SimpleResult<Option<G>> {
+        let group = match self.get_entry(vector, index) {
+            Some(e) => {
+                self._check_not_frozen(&e.entry.linked)?;
+                e.entry.group
+            },
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+
+        let g = self.groups.entry(group).or_insert_with(|| Group::new(None, GroupOptions::default()));
+
+        Ok(mem::replace(&mut g.data, data))
+    }
+
+    /// Returns the options of the group that the entry at the given index
+    /// belongs to (see `insert_entries_with_options()`).
+    ///
+    /// Returns `None` if the entry doesn't exist.
+    pub fn group_options(&self, vector: &N, index: u64) -> Option<&GroupOptions> {
+        let group = self.get_entry(vector, index)?.entry.group;
+
+        Some(&self.groups.get(&group)?.options)
+    }
+
+    /// Returns the id of the group that a group is nested inside (see
+    /// `GroupOptions::parent`).
+    ///
+    /// Group ids are found in `MultiEntry::group`. Returns `None` if the group
+    /// doesn't exist, or isn't nested.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, GroupOptions};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // A struct...
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    /// ]).unwrap();
+    /// let outer = mv.get_entry(&"myvector", 0).unwrap().entry.group;
+    ///
+    /// // ...with a struct inside it
+    /// mv.insert_entries_with_options(vec![
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 333, index: 20, size: 10 }),
+    /// ], None, GroupOptions { parent: Some(outer), ..Default::default() }).unwrap();
+    /// let inner = mv.get_entry(&"myvector", 10).unwrap().entry.group;
+    ///
+    /// assert_eq!(Some(outer), mv.parent_group(inner));
+    /// assert_eq!(vec![inner], mv.child_groups(outer));
+    ///
+    /// // Removing the inner struct leaves the outer one alone
+    /// assert_eq!(2, mv.remove_entries(&"myvector", 20).unwrap().len());
+    /// assert_eq!(1, mv.len());
+    /// ```
+    pub fn parent_group(&self, group: usize) -> Option<usize> {
+        self.groups.get(&group)?.options.parent
+    }
+
+    /// Returns the ids of the groups nested directly inside a group, in the
+    /// order they were created.
+    pub fn child_groups(&self, group: usize) -> Vec<usize> {
+        self._child_groups(group)
+    }
+
+    /// Returns the id of a group followed by the ids of every group nested
+    /// inside it, at any depth (depth-first, in the order they were created).
+    ///
+    /// Returns an empty vector if the group doesn't exist.
+    pub fn group_tree(&self, group: usize) -> Vec<usize> {
+        if !self.groups.contains_key(&group) {
+            return Vec::new();
+        }
+
+        self._group_tree(group)
+    }
+
+    /// Returns the entries in a group, given its id, in the order they were
+    /// inserted.
+    ///
+    /// Groups don't track where their members live, so this has to search
+    /// every vector; it's not fast.
+    pub fn group_entries(&self, group: usize) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        self._linked_entries(&self._group_members(group), group)
+    }
+
+    /// Look up the `members` of a group.
+    ///
+    /// This is for internal use only.
+    fn _linked_entries(&self, members: &[(N, u64)], group: usize) -> Vec<&BumpyEntry<MultiEntry<N, T>>> {
+        members.iter().filter_map(|(vector, index)| {
+            self.vectors.get(vector)?.entries.get_in_group(*index, group)
+        }).collect()
+    }
+
+    /// Split an entry into several adjacent entries.
+    ///
+    /// The entry at `index` is replaced by one entry per piece - the pieces
+    /// start at the entry's start, then at each of the split points in `at`
+    /// (which must be in order, and inside the entry). The `splitter` is
+    /// given the entry's data, and has to return the pieces, in order, with
+    /// ranges that match.
+    ///
+    /// The pieces take the original entry's place in its group, so they're
+    /// linked to each other and to the rest of the group.
+    ///
+    /// The splitter is given a clone of the data, so that nothing is changed
+    /// if the pieces it returns don't line up.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message on failure. On failure, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// #[derive(Clone)]
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // A struct with a header and a 16-byte blob
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size:  4 }),
+    ///     (&"myvector", MyEntryType { data: 222, index:  4, size: 16 }),
+    /// ]).unwrap();
+    ///
+    /// // Turn the blob into four 32-bit fields
+    /// mv.split_entry(&"myvector", 4, &[8, 12, 16], |blob| {
+    ///     (0..4).map(|i| MyEntryType { data: blob.data, index: blob.index + (i * 4), size: 4 }).collect()
+    /// }).unwrap();
+    ///
+    /// // They're all still in the same group
+    /// assert_eq!(5, mv.len());
+    /// assert_eq!(5, mv.get_entries(&"myvector", 0).unwrap().len());
+    /// ```
+    pub fn split_entry<F>(&mut self, vector: &N, index: u64, at: &[u64], splitter: F) -> SimpleResult<()>
+    where
+        T: Clone,
+        F: FnOnce(T) -> Vec<T>,
+    {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        let e = match v.entries.get(index) {
+            Some(e) => e,
+            None => bail!("Couldn't find index {} in vector {:?}", index, vector),
+        };
+        self._check_not_frozen(&e.entry.linked)?;
+
+        // Work out the range of each piece
+        let original = to_u64_range(&e.range);
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        let mut start = original.start;
+        for split in at {
+            if *split <= start || *split >= original.end {
+                bail!("Split point {} isn't in order, or isn't inside the entry at {:?}", split, original);
+            }
+
+            ranges.push(start..*split);
+            start = *split;
+        }
+        ranges.push(start..original.end);
+
+        // Make sure the pieces line up
+        let pieces = splitter(e.entry.data.clone());
+        if pieces.len() != ranges.len() {
+            bail!("Expected {} pieces, got {}", ranges.len(), pieces.len());
+        }
+
+        for (piece, range) in pieces.iter().zip(ranges.iter()) {
+            if v.to_index_range(to_u64_range(&piece.range()))? != *range {
+                bail!("Piece at {:?} doesn't match the expected range {:?}", piece.range(), range);
+            }
+
+            v.check_layout(range)?;
+        }
+
+        // The pieces replace the original in the group's links
+        let linked = e.entry.linked.clone();
+        let group = e.entry.group;
+        let pinned = e.entry.pinned;
+        let tags = e.entry.tags.clone();
+        let mut weak = e.entry.weak.clone();
+        let mut new_linked: Vec<(N, u64)> = Vec::new();
+        for (linked_vector, linked_index) in linked.iter() {
+            if linked_vector == vector && *linked_index == original.start {
+                new_linked.extend(ranges.iter().map(|range| (vector.clone(), range.start)));
+            } else {
+                new_linked.push((linked_vector.clone(), *linked_index));
+            }
+        }
+
+        // In a vector with overlapping entries, another member of the group
+        // could already start where a piece does
+        let unique: HashSet<&(N, u64)> = new_linked.iter().collect();
+        if unique.len() != new_linked.len() {
+            bail!("A piece would start at the same index as another entry in its group");
+        }
+
+        // Swap the pieces in
+        if let Some(v) = self.vectors.get_mut(vector) {
+            v.entries.remove_in_group(original.start, group);
+
+            for (piece, range) in pieces.into_iter().zip(ranges) {
+                // This can't fail, the pieces exactly fill the original's space
+                v.entries.insert(BumpyEntry {
+                    entry: MultiEntry {
+                        vector: vector.clone(),
+                        data: piece,
+                        linked: new_linked.clone(),
+                        group,
+                        pinned,
+
+                        // The first piece starts where the original did, so
+                        // it keeps the weak links
+                        weak: mem::take(&mut weak),
+
+                        // Every piece keeps the tags
+                        tags: tags.clone(),
+                    },
+                    range: to_usize_range(&range)?,
+                })?;
+            }
+        }
+
+        self._set_links(&linked, &new_linked, group);
+
+        Ok(())
+    }
+
+    /// Merge a run of adjacent entries into a single entry.
+    ///
+    /// This is the opposite of `split_entry()`. The entries in `range` must
+    /// fill it exactly - no gaps, and nothing straddling the edges. The
+    /// `combiner` is given their data, in order, and has to return a single
+    /// entry that covers the whole range.
+    ///
+    /// The merged entry takes the place of the first entry in its group. If
+    /// the entries were in different groups, those groups are merged into
+    /// the first one, so everything that was linked to any of the entries is
+    /// linked to the merged entry.
+    /// Groups that were nested inside the merged groups are nested inside the
+    /// first one.
+    ///
+    /// The combiner is given clones of the data, so that nothing is changed
+    /// if the entry it returns doesn't line up.
+    ///
+    /// # Return
+    ///
+    /// Returns the group data of any groups that were merged into the first
+    /// one (since a group only has room for one), or `Err()` with a
+    /// descriptive error message on failure. On failure, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// #[derive(Clone)]
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    ///
+    /// // An array of bytes
+    /// mv.insert_entries((0..8).map(|i| {
+    ///     (&"myvector", MyEntryType { data: 111, index: i, size: 1 })
+    /// }).collect()).unwrap();
+    /// assert_eq!(8, mv.len());
+    ///
+    /// // Collapse them into a single entry
+    /// mv.merge_entries(&"myvector", 0..8, |bytes| {
+    ///     MyEntryType { data: 222, index: 0, size: bytes.len() }
+    /// }).unwrap();
+    /// assert_eq!(1, mv.len());
+    /// assert_eq!(222, mv.get_entry(&"myvector", 7).unwrap().entry.data.data);
+    /// ```
+    pub fn merge_entries<F>(&mut self, vector: &N, range: Range<u64>, combiner: F) -> SimpleResult<Vec<G>>
+    where
+        T: Clone,
+        F: FnOnce(Vec<T>) -> T,
+    {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        // Make sure the entries exactly fill the range
+        let run = v.get_range(range.clone());
+        let mut next_start = range.start;
+        for e in run.iter() {
+            if to_u64(e.range.start) != next_start {
+                bail!("Entries in {:?} aren't contiguous at index {}", range, next_start);
+            }
+
+            next_start = to_u64(e.range.end);
+        }
+
+        if run.is_empty() || next_start != range.end {
+            bail!("Entries in {:?} aren't contiguous at index {}", range, next_start);
+        }
+
+        // Make sure the merged entry lines up
+        let merged = combiner(run.iter().map(|e| e.entry.data.clone()).collect());
+        if v.to_index_range(to_u64_range(&merged.range()))? != range {
+            bail!("Merged entry at {:?} doesn't match the expected range {:?}", merged.range(), range);
+        }
+        v.check_layout(&range)?;
+        let merged_range = to_usize_range(&range)?;
+
+        // Combine the links from every group involved - the merged entry goes
+        // where the first entry was, and the rest of the run goes away
+        let starts: Vec<(u64, usize)> = run.iter().map(|e| (to_u64(e.range.start), e.entry.group)).collect();
+        let group = run[0].entry.group;
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut others: Vec<usize> = Vec::new();
+        let mut new_linked: Vec<(N, u64)> = Vec::new();
+        let mut members: Vec<(N, u64, usize)> = Vec::new();
+        for e in run.iter() {
+            if !seen.insert(e.entry.group) {
+                continue;
+            }
+
+            if e.entry.group != group {
+                others.push(e.entry.group);
+            }
+
+            for (linked_vector, linked_index) in e.entry.linked.iter() {
+                if linked_vector == vector && starts.contains(&(*linked_index, e.entry.group)) {
+                    if *linked_index == range.start {
+                        new_linked.push((vector.clone(), range.start));
+                    }
+                } else {
+                    new_linked.push((linked_vector.clone(), *linked_index));
+                    members.push((linked_vector.clone(), *linked_index, e.entry.group));
+                }
+            }
+        }
+
+        // In a vector with overlapping entries, two of the groups could have
+        // members at the same index
+        let unique: HashSet<&(N, u64)> = new_linked.iter().collect();
+        if unique.len() != new_linked.len() {
+            bail!("Merging the groups would put two entries at the same index");
+        }
+        self._check_not_frozen(&new_linked)?;
+
+        // The merged entry is pinned if any of the run was, and has all of
+        // their tags
+        let pinned = run.iter().any(|e| e.entry.pinned);
+        let mut tags: Vec<String> = Vec::new();
+        for tag in run.iter().flat_map(|e| e.entry.tags.iter()) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+
+        // It also takes over the run's weak links (except the ones between
+        // members of the run) - (partner, old start) for each one that has to
+        // be pointed at the merged entry
+        let mut weak: Vec<(N, u64)> = Vec::new();
+        let mut moved_weak: Vec<((N, u64), u64)> = Vec::new();
+        for e in run.iter() {
+            for partner in e.entry.weak.iter() {
+                if partner.0 == *vector && starts.iter().any(|(start, _)| *start == partner.1) {
+                    continue;
+                }
+
+                if !weak.contains(partner) {
+                    weak.push(partner.clone());
+                }
+
+                if to_u64(e.range.start) != range.start {
+                    moved_weak.push((partner.clone(), to_u64(e.range.start)));
+                }
+            }
+        }
+
+        // Swap the merged entry in
+        if let Some(v) = self.vectors.get_mut(vector) {
+            for (start, group) in starts {
+                v.entries.remove_in_group(start, group);
+            }
+
+            // This can't fail, it exactly fills the space we just emptied
+            v.entries.insert(BumpyEntry {
+                entry: MultiEntry {
+                    vector: vector.clone(),
+                    data: merged,
+                    linked: new_linked.clone(),
+                    group,
+                    pinned,
+                    weak,
+                    tags,
+                },
+                range: merged_range,
+            })?;
+        }
+
+        for (partner, old_start) in moved_weak {
+            self._retarget_weak(&partner, &(vector.clone(), old_start), Some(&(vector.clone(), range.start)));
+        }
+
+        // Move the rest of the members into the first group
+        for (linked_vector, linked_index, old_group) in members {
+            if let Some(e) = self.vectors.get_mut(&linked_vector).and_then(|v| v.entries.get_mut_in_group(linked_index, old_group)) {
+                e.entry.linked = new_linked.clone();
+                e.entry.group = group;
+            }
+        }
+
+        // Groups nested inside the others are nested inside the merged group
+        // now - except the merged group's own ancestors, which just move up a
+        // level when their parent goes away
+        let mut ancestors: Vec<usize> = Vec::new();
+        let mut parent = self.groups.get(&group).and_then(|g| g.options.parent);
+        while let Some(p) = parent {
+            ancestors.push(p);
+            parent = self.groups.get(&p).and_then(|g| g.options.parent);
+        }
+
+        let mut adopted: Vec<usize> = Vec::new();
+        for other in others.iter() {
+            if let Some(g) = self.groups.get(other) {
+                adopted.extend(g.children.iter().filter(|id| **id != group && !ancestors.contains(id)));
+            }
+        }
+
+        for child in adopted {
+            self._set_parent(child, Some(group));
+        }
+
+        // Get rid of the other groups
+        let mut data: Vec<G> = Vec::new();
+        for other in others {
+            if let Some(Group { data: Some(d), .. }) = self._drop_group(other) {
+                data.push(d);
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Insert empty space into a vector, shifting later entries forward.
+    ///
+    /// This is for when the underlying buffer has bytes inserted. `len`
+    /// indexes are added at index `at`, and every entry that starts at or
+    /// after `at` moves forward by `len`. The vector's `max_size` grows by
+    /// `len`.
+    ///
+    /// Links pointing to the moved entries (in any vector) are updated, as are
+    /// the origins of child vectors.
+    ///
+    /// Space can't be inserted in the middle of an entry, or in the middle of
+    /// a child vector's origin.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message on failure. On failure, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, MovableEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    /// impl MovableEntry for MyEntryType {
+    ///     fn move_to(&mut self, start: usize) { self.index = start; }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Insert 5 bytes between the two entries
+    /// mv.insert_space(&"myvector", 10, 5).unwrap();
+    /// assert_eq!(105, mv.max_size_vector(&"myvector").unwrap());
+    ///
+    /// // The second entry moved
+    /// assert_eq!(15, mv.get_entry(&"myvector", 15).unwrap().entry.data.index);
+    /// assert!(mv.get_entry(&"myvector", 10).is_none());
+    ///
+    /// // Delete them again
+    /// mv.delete_space(&"myvector", 10..15).unwrap();
+    /// assert_eq!(100, mv.max_size_vector(&"myvector").unwrap());
+    /// assert_eq!(10, mv.get_entry(&"myvector", 10).unwrap().entry.data.index);
+    /// ```
+    pub fn insert_space(&mut self, vector: &N, at: u64, len: u64) -> SimpleResult<()>
+    where
+        T: MovableEntry,
+    {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        if at > v.entries.max_size() {
+            bail!("Index {} is outside of vector {:?}", at, vector);
+        }
+
+        // The vector's last address has to fit, as well as its size
+        let new_size = match v.entries.max_size().checked_add(len) {
+            Some(s) if v.to_address(s).is_ok() => s,
+            _ => bail!("Vector {:?} would be too large", vector),
+        };
+
+        // Make sure we aren't splitting an entry
+        for e in v.entries.get_all(at) {
+            if to_u64(e.range.start) != at {
+                bail!("Can't insert space inside the entry at {:?}", e.range);
+            }
+        }
+
+        // Make sure we aren't splitting a child's origin
+        for (name, child) in self.vectors.iter() {
+            if let Some((parent, origin)) = &child.parent {
+                if parent == vector && origin.start < at && at < origin.end {
+                    bail!("Can't insert space inside the origin of child vector {:?}", name);
+                }
+            }
+        }
+
+        let f = |start| {
+            if start >= at {
+                start + len
+            } else {
+                start
+            }
+        };
+
+        self._relocate(vector, new_size, f)?;
+        self._move_child_origins(vector, f);
+
+        Ok(())
+    }
+
+    /// Delete a range of space from a vector, shifting later entries back.
+    ///
+    /// This is the opposite of `insert_space()`, for when the underlying
+    /// buffer has bytes removed. The range is removed, every entry that starts
+    /// after it moves back by its length, and the vector's `max_size` shrinks
+    /// to match.
+    ///
+    /// Entries entirely inside the range are removed, along with the rest of
+    /// their groups (just like `remove_entries()`). Entries that straddle the
+    /// edge of the range can't be shrunk, so the deletion fails instead; the
+    /// same is true of child vectors whose origin overlaps the range.
+    ///
+    /// Links pointing to the moved entries (in any vector) are updated, as are
+    /// the origins of child vectors.
+    ///
+    /// # Return
+    ///
+    /// Returns the groups that were removed, or `Err()` with a descriptive
+    /// error message on failure. On failure, nothing is changed.
+    pub fn delete_space(&mut self, vector: &N, range: Range<u64>) -> SimpleResult<Vec<RemovedGroup<N, T, G>>>
+    where
+        T: MovableEntry,
+    {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        if range.start >= range.end || range.end > v.entries.max_size() {
+            bail!("Range {:?} isn't a valid range in vector {:?}", range, vector);
+        }
+
+        let len = range.end - range.start;
+        let new_size = v.entries.max_size() - len;
+
+        // Check this up front, since removing the groups would succeed
+        self._check_links_not_frozen(vector)?;
+
+        // Make sure no entries straddle the edges
+        for e in v.get_range(range.clone()) {
+            if to_u64(e.range.start) < range.start || to_u64(e.range.end) > range.end {
+                bail!("Can't delete space from the entry at {:?}", e.range);
+            }
+        }
+
+        // Make sure we aren't deleting part of a child's origin
+        for (name, child) in self.vectors.iter() {
+            if let Some((parent, origin)) = &child.parent {
+                if parent == vector && origin.start < range.end && range.start < origin.end {
+                    bail!("Can't delete space from the origin of child vector {:?}", name);
+                }
+            }
+        }
+
+        let f = |start| {
+            if start >= range.end {
+                start - len
+            } else {
+                start
+            }
+        };
+
+        // Make sure whatever's left can be moved, before removing anything
+        let removals = self._range_removals(vector, range.clone());
+        self._plan_relocation(vector, new_size, &f, &removals)?;
+
+        // Remove the entries in the deleted space
+        let removed = self.remove_range(vector, range.clone())?;
+
+        self._relocate(vector, new_size, f)?;
+        self._move_child_origins(vector, f);
+
+        Ok(removed)
+    }
+
+    /// Remove every group that has at least one entry overlapping a range.
+    ///
+    /// This is the "undefine everything in this selection" operation. Any
+    /// entry in `vector` that overlaps `range` (even partially) is removed,
+    /// along with the rest of its group - including members in other vectors -
+    /// and any groups nested inside it.
+    ///
+    /// # Return
+    ///
+    /// Returns the groups that were removed, each one once, in a form that
+    /// can be passed to `restore_groups()`. Returns `Err` with a descriptive
+    /// error message if the vector doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 100).unwrap();
+    /// mv.create_vector("vector2", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 111, index:  0, size: 10 }),
+    ///     (&"vector1", MyEntryType { data: 222, index: 10, size: 10 }),
+    /// ]).unwrap();
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 333, index: 50, size: 10 }),
+    ///     (&"vector2", MyEntryType { data: 444, index:  0, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Undefine the middle of the vector
+    /// let removed = mv.remove_range(&"vector1", 15..55).unwrap();
+    /// assert_eq!(2, removed.len());
+    /// assert_eq!(0, mv.len());
+    ///
+    /// // Put it all back
+    /// mv.restore_groups(removed).unwrap();
+    /// assert_eq!(4, mv.len());
+    /// ```
+    pub fn remove_range(&mut self, vector: &N, range: Range<u64>) -> SimpleResult<Vec<RemovedGroup<N, T, G>>> {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+        let starts: Vec<(u64, usize)> = v.get_range(range.clone()).iter().map(|e| (to_u64(e.range.start), e.entry.group)).collect();
+
+        // Make sure every group can be removed before removing any, and work
+        // out which ones are going entirely
+        let mut whole: HashSet<usize> = HashSet::new();
+        for e in v.get_range(range) {
+            let group = e.entry.group;
+            self._check_not_frozen(&e.entry.linked)?;
+
+            match self._group_options(group).removal {
+                RemovalPolicy::Cascade => self._check_not_pinned(&e.entry.linked, group)?,
+                RemovalPolicy::RemoveSelf => self._check_not_pinned(&[(vector.clone(), to_u64(e.range.start))], group)?,
+                RemovalPolicy::Refuse => {
+                    // That's fine, as long as the whole group is going
+                    if e.entry.linked.iter().any(|(linked_vector, linked_index)| linked_vector != vector || !starts.contains(&(*linked_index, group))) {
+                        bail!("The entry at index {} in vector {:?} is linked to entries outside of the range, and its group can't be removed", e.range.start, vector);
+                    }
+
+                    self._check_not_pinned(&e.entry.linked, group)?;
+                },
+            };
+
+            // If the whole group is going, so are the groups nested inside it
+            if self._removes_whole_group(vector, &e.entry, &starts) {
+                self._descendants_to_remove(group, false)?;
+                whole.insert(group);
+            }
+        }
+
+        let mut removed: Vec<RemovedGroup<N, T, G>> = Vec::new();
+        for (index, group) in starts {
+            // It might be gone already, if it was grouped with an earlier one
+            if self.vectors.get(vector).and_then(|v| v.entries.get_in_group(index, group)).is_none() {
+                continue;
+            }
+
+            if !whole.contains(&group) {
+                removed.push(self._remove_self(vector, index, group)?);
+            } else {
+                // Remove the nested groups from the bottom up, so each one is
+                // still nested when it's removed, but return them from the top
+                // down, so they can be restored in order
+                let mut tree: Vec<RemovedGroup<N, T, G>> = Vec::new();
+                for (descendant, members) in self._descendant_members(group).into_iter().rev() {
+                    if let Some((linked_vector, linked_index)) = members.first() {
+                        tree.push(self._remove_group(linked_vector, *linked_index, descendant)?);
+                    }
+                }
+                tree.push(self._remove_group(vector, index, group)?);
+
+                removed.extend(tree.into_iter().rev());
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Put back groups that were removed.
+    ///
+    /// Each group is re-inserted, with its group data, exactly as
+    /// `insert_entries_with()` would. This is meant for undoing operations
+    /// like `remove_range()`.
+    ///
+    /// Nested groups (see `GroupOptions::parent`) are nested again, as long as
+    /// their parent was restored along with them or was never removed.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if every group was restored. If any group can't be
+    /// restored (because something else is in the way, for example), the
+    /// groups that were already restored are removed again and `Err` is
+    /// returned with a descriptive error message; the groups are consumed
+    /// either way.
+    pub fn restore_groups(&mut self, groups: Vec<RemovedGroup<N, T, G>>) -> SimpleResult<()> {
+        // The first entry of each group we've restored, in case we need to
+        // back them out
+        let mut backtrack: Vec<(N, u64, usize)> = Vec::new();
+
+        // The new id of each restored group (keyed by its old id), and the
+        // old parent of each, so the nesting can be put back once every group
+        // exists again
+        let mut new_ids: HashMap<usize, usize> = HashMap::new();
+        let mut parents: Vec<(usize, usize)> = Vec::new();
+
+        for mut group in groups {
+            let first = match group.entries.first() {
+                Some(e) => (e.entry.vector.clone(), to_u64(e.range.start), self.next_group_id),
+                None => continue,
+            };
+            new_ids.insert(group.entries[0].entry.group, first.2);
+            if let Some(parent) = group.options.parent.take() {
+                parents.push((first.2, parent));
+            }
+
+            let mut vectors: Vec<N> = Vec::new();
+            let mut entries: Vec<T> = Vec::new();
+            let mut pinned: Vec<(N, u64)> = Vec::new();
+            let mut tags: Vec<((N, u64), String)> = Vec::new();
+            let mut weak = Vec::new();
+            for e in group.entries {
+                if e.entry.pinned {
+                    pinned.push((e.entry.vector.clone(), to_u64(e.range.start)));
+                }
+
+                for tag in e.entry.tags {
+                    tags.push(((e.entry.vector.clone(), to_u64(e.range.start)), tag));
+                }
+
+                if !e.entry.weak.is_empty() {
+                    weak.push(((e.entry.vector.clone(), to_u64(e.range.start)), e.entry.weak));
+                }
+
+                vectors.push(e.entry.vector);
+                entries.push(e.entry.data);
+            }
+
+            if let Err(e) = self._insert_entries(vectors.iter().zip(entries).collect(), group.data, group.options) {
+                for (vector, index, group) in backtrack {
+                    self._remove_entries(&vector, index, Some(group), true)?;
+                }
+
+                bail!("Couldn't restore group: {}", e);
+            }
+
+            // Put the pins back
+            for (vector, index) in pinned {
+                if let Some(e) = self.vectors.get_mut(&vector).and_then(|v| v.entries.get_mut_in_group(index, first.2)) {
+                    e.entry.pinned = true;
+                }
+            }
+
+            // And the tags
+            for ((vector, index), tag) in tags {
+                if let Some(v) = self.vectors.get_mut(&vector) {
+                    v.entries.add_tag(index, first.2, &tag);
+                }
+            }
+
+            // Put back the weak links to anything that's still around
+            for (me, partners) in weak {
+                let mut kept: Vec<(N, u64)> = Vec::new();
+                for partner in partners {
+                    if let Some(e) = self.vectors.get_mut(&partner.0).and_then(|v| v.entries.get_mut(partner.1)) {
+                        if to_u64(e.range.start) == partner.1 {
+                            if !e.entry.weak.contains(&me) {
+                                e.entry.weak.push(me.clone());
+                            }
+                            kept.push(partner);
+                        }
+                    }
+                }
+
+                if let Some(e) = self.vectors.get_mut(&me.0).and_then(|v| v.entries.get_mut_in_group(me.1, first.2)) {
+                    e.entry.weak = kept;
+                }
+            }
+
+            backtrack.push(first);
+        }
+
+        // Nest the groups again - the parent is either another restored group,
+        // or one that was never removed
+        for (group, parent) in parents {
+            let parent = new_ids.get(&parent).copied().unwrap_or(parent);
+
+            if self.groups.contains_key(&parent) {
+                self._set_parent(group, Some(parent));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move every entry in a vector by the same amount.
+    ///
+    /// This is for when a vector's entries turn out to be at the wrong offset
+    /// (for example, a blob that was extracted from the wrong place). Every
+    /// entry moves by `delta` - forwards if it's positive, backwards if it's
+    /// negative - and links to the moved entries (in any vector) are updated.
+    ///
+    /// The vector's size doesn't change, and neither do the origins of its
+    /// child vectors, since the underlying data hasn't moved.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the vector doesn't exist or any entry would end up outside
+    /// of it. On failure, nothing is changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry, MovableEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    /// impl MovableEntry for MyEntryType {
+    ///     fn move_to(&mut self, start: usize) { self.index = start; }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("myvector", 100).unwrap();
+    /// mv.insert_entries(vec![
+    ///     (&"myvector", MyEntryType { data: 111, index: 10, size: 10 }),
+    ///     (&"myvector", MyEntryType { data: 222, index: 20, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Move everything back by 10
+    /// mv.rebase_vector(&"myvector", -10).unwrap();
+    /// assert_eq!(0, mv.get_entry(&"myvector", 0).unwrap().entry.data.index);
+    /// assert_eq!(10, mv.get_entry(&"myvector", 10).unwrap().entry.data.index);
+    ///
+    /// // Can't move them off the start
+    /// assert!(mv.rebase_vector(&"myvector", -1).is_err());
+    /// ```
+    pub fn rebase_vector(&mut self, vector: &N, delta: i64) -> SimpleResult<()>
+    where
+        T: MovableEntry,
+    {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        let max_size = v.entries.max_size();
+        let distance = delta.unsigned_abs();
+
+        // Make sure everything will still fit - this has to happen up front,
+        // since the moves themselves would overflow
+        for e in v.entries.into_iter() {
+            if delta < 0 && to_u64(e.range.start) < distance {
+                bail!("Entry at {:?} would be moved off the start of vector {:?}", e.range, vector);
+            }
+
+            if delta > 0 && max_size - to_u64(e.range.end) < distance {
+                bail!("Entry at {:?} would be moved off the end of vector {:?}", e.range, vector);
+            }
+        }
+
+        self._relocate(vector, max_size, |start| {
+            if delta < 0 {
+                start - distance
+            } else {
+                start + distance
+            }
+        })
+    }
+
+    /// Returns the number of vectors in the `MultiVector`.
+    pub fn vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Returns `true` if a vector with the given name exists, false otherwise.
+    pub fn vector_exists(&self, vector: &N) -> bool {
+        self.vectors.contains_key(vector)
+    }
+
+    /// Returns the number of elements in the given vector; `None` otherwise.
+    pub fn len_vector(&self, vector: &N) -> Option<usize> {
+        let v = self.vectors.get(vector)?;
+
+        Some(v.entries.len())
+    }
+
+    /// Returns the max size of the named Vector; `None` if not found.
+    pub fn max_size_vector(&self, vector: &N) -> Option<u64> {
+        let v = self.vectors.get(vector)?;
+
+        Some(v.entries.max_size())
+    }
+
+    /// Returns the parent of the named vector, and the range within the parent
+    /// that it was derived from; `None` if not found, or if it has no parent.
+    pub fn parent_of(&self, vector: &N) -> Option<(&N, Range<u64>)> {
+        let (parent, origin) = self.vectors.get(vector)?.parent.as_ref()?;
+
+        Some((parent, origin.clone()))
+    }
+
+    /// Returns the names of all vectors that were derived directly from the
+    /// named vector.
+    pub fn children_of(&self, vector: &N) -> Vec<&N> {
+        self.vectors.iter().filter(|(_, v)| {
+            match &v.parent {
+                Some((parent, _)) => parent == vector,
+                None => false,
+            }
+        }).map(|(name, _)| name).collect()
+    }
+
+    /// Translate an index in a child vector into the matching index in its
+    /// parent vector.
+    ///
+    /// A child vector maps one-to-one onto its origin range within the parent
+    /// - index `0` in the child is `origin.start` in the parent, and so on.
+    ///
+    /// # Return
+    ///
+    /// Returns the parent's name and the translated index, or `None` if the
+    /// vector doesn't exist, has no parent, or the index falls outside of the
+    /// origin range.
+    pub fn translate(&self, vector: &N, index: u64) -> Option<(N, u64)> {
+        let v = self.vectors.get(vector)?;
+        let (parent, origin) = v.parent.as_ref()?;
+
+        if index >= v.entries.max_size() || index >= origin.end - origin.start {
+            return None;
+        }
+
+        Some((parent.clone(), origin.start + index))
+    }
+
+    /// Translate an index in a child vector all the way up to the vector that
+    /// it was ultimately derived from.
+    ///
+    /// This follows `translate()` up through as many levels of parents as
+    /// there are - for example, from a file in an archive, to the archive, to
+    /// the disk image the archive was found in.
+    ///
+    /// # Return
+    ///
+    /// Returns the root vector's name and the translated index, or `None` if
+    /// the vector doesn't exist, has no parent, or the index can't be
+    /// translated at some level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // A file, containing an archive, containing a member
+    /// mv.create_vector("file", 1000).unwrap();
+    /// mv.create_child_vector(&"file", "archive", 500, 100..600).unwrap();
+    /// mv.create_child_vector(&"archive", "member", 100, 200..300).unwrap();
+    ///
+    /// // Index 10 in the member is 210 in the archive, which is 310 in the file
+    /// assert_eq!(Some(("archive", 210)), mv.translate(&"member", 10));
+    /// assert_eq!(Some(("file", 310)), mv.translate_to_root(&"member", 10));
+    ///
+    /// // And back down again
+    /// assert_eq!(vec![("archive", 210), ("member", 10)], mv.translate_reverse(&"file", 310));
+    /// ```
+    pub fn translate_to_root(&self, vector: &N, index: u64) -> Option<(N, u64)> {
+        let mut result = self.translate(vector, index)?;
+
+        while self.parent_of(&result.0).is_some() {
+            result = self.translate(&result.0, result.1)?;
+        }
+
+        Some(result)
+    }
+
+    /// Translate an index in a vector into the matching index in every vector
+    /// that was derived from it.
+    ///
+    /// This is the reverse of `translate()`, and follows the children down
+    /// through as many levels as there are.
+    ///
+    /// # Return
+    ///
+    /// Returns a list of vector names and the translated index in each. Each
+    /// child comes before its own children, but the order is otherwise
+    /// undefined. If the vector doesn't exist, or no children contain the
+    /// index, the list is empty.
+    pub fn translate_reverse(&self, vector: &N, index: u64) -> Vec<(N, u64)> {
+        let mut results: Vec<(N, u64)> = Vec::new();
+
+        for (name, v) in self.vectors.iter() {
+            let origin = match &v.parent {
+                Some((parent, origin)) if parent == vector => origin,
+                _ => continue,
+            };
+
+            if !origin.contains(&index) || index - origin.start >= v.entries.max_size() {
+                continue;
+            }
+
+            let child_index = index - origin.start;
+            results.push((name.clone(), child_index));
+            results.append(&mut self.translate_reverse(name, child_index));
+        }
+
+        results
+    }
+
+    /// Set (or clear) the base address of the named vector.
+    ///
+    /// Once a vector has a base address, it's addressed by that rather than
+    /// by a zero-based index - the `range()` of entries passed to
+    /// `insert_entries()` is treated as an address range, and
+    /// `get_entry_at_address()` and `get_range_at_address()` can be used to
+    /// find entries. Addresses outside of `[base, base + max_size)` are an
+    /// error.
+    ///
+    /// Internally (and in `linked`), entries are still tracked by index.
+    ///
+    /// The base address can only be changed while the vector is empty, since
+    /// the entries' own ranges can't be changed. It also has to leave room
+    /// for the whole vector: `base + max_size` can't overflow a `u64`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message on failure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, address: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.address..(self.address + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    ///
+    /// // A 0x100-byte image, loaded at 0x400000
+    /// mv.create_vector("image", 0x100).unwrap();
+    /// mv.set_base_address(&"image", Some(0x400000)).unwrap();
+    ///
+    /// // Insert an entry by address
+    /// mv.insert_entry(&"image", MyEntryType { data: 111, address: 0x400010, size: 4 }).unwrap();
+    ///
+    /// // It can be found by either address or index
+    /// assert_eq!(111, mv.get_entry_at_address(&"image", 0x400012).unwrap().entry.data.data);
+    /// assert_eq!(111, mv.get_entry(&"image", 0x12).unwrap().entry.data.data);
+    ///
+    /// // Addresses outside of the image are an error
+    /// assert!(mv.insert_entry(&"image", MyEntryType { data: 222, address: 0x10, size: 4 }).is_err());
+    /// ```
+    pub fn set_base_address(&mut self, vector: &N, base_address: Option<u64>) -> SimpleResult<()> {
+        let v = match self.vectors.get_mut(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        if v.entries.len() != 0 {
+            bail!("Can't change the base address of a vector that isn't empty");
+        }
+
+        if v.frozen {
+            bail!("Vector {:?} is frozen", vector);
+        }
+
+        // Every address in the vector has to fit in a u64
+        if let Some(base) = base_address {
+            if base.checked_add(v.entries.max_size()).is_none() {
+                bail!("Base address {} is too high for vector {:?}", base, vector);
+            }
+        }
+
+        v.base_address = base_address;
+
+        Ok(())
+    }
+
+    /// Returns the base address of the named vector; `None` if not found, or
+    /// if it has no base address.
+    pub fn base_address(&self, vector: &N) -> Option<u64> {
+        self.vectors.get(vector)?.base_address
+    }
+
+    /// Convert an address in the named vector to an index.
+    ///
+    /// If the vector has no base address, addresses and indexes are the same
+    /// thing.
+    ///
+    /// Returns `Err` with a descriptive error message if the vector doesn't
+    /// exist, or the address isn't within the vector.
+    pub fn address_to_index(&self, vector: &N, address: u64) -> SimpleResult<u64> {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        let base = v.base_address.unwrap_or(0);
+        if address < base || address - base >= v.entries.max_size() {
+            bail!("Address {} is outside of vector {:?}", address, vector);
+        }
+
+        Ok(address - base)
+    }
+
+    /// Convert an index in the named vector to an address.
+    ///
+    /// Returns `Err` with a descriptive error message if the vector doesn't
+    /// exist, or the index isn't within the vector.
+    pub fn index_to_address(&self, vector: &N, index: u64) -> SimpleResult<u64> {
+        let v = match self.vectors.get(vector) {
+            Some(v) => v,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        if index >= v.entries.max_size() {
+            bail!("Index {} is outside of vector {:?}", index, vector);
+        }
+
+        v.to_address(index)
+    }
+
+    /// Returns the options the named vector was created with (see
+    /// `create_vector_with_options()`); `None` if not found.
+    pub fn vector_options(&self, vector: &N) -> Option<&VectorOptions> {
+        let v = self.vectors.get(vector)?;
+
+        Some(&v.options)
+    }
+
+    /// Returns a reference to the metadata of the named vector; `None` if
+    /// not found.
+    pub fn vector_meta(&self, vector: &N) -> Option<&M> {
+        let v = self.vectors.get(vector)?;
+
+        Some(&v.meta)
+    }
+
+    /// Returns a mutable reference to the metadata of the named vector;
+    /// `None` if not found.
+    pub fn vector_meta_mut(&mut self, vector: &N) -> Option<&mut M> {
+        let v = self.vectors.get_mut(vector)?;
+
+        Some(&mut v.meta)
+    }
+
+    /// Freeze a vector, so its entries can't be changed.
+    ///
+    /// This is for vectors whose analysis is finished, and that shouldn't be
+    /// edited by accident. While a vector is frozen, anything that would add,
+    /// remove, move, relink, tag, or pin its entries fails - including
+    /// removing or unlinking a group from one of its members in another
+    /// vector, since that would change the entries in this one. Group data
+    /// can't be changed either (`group_data_mut()` returns an error), if the
+    /// group has a member in a frozen vector.
+    ///
+    /// `force_destroy_vector()` ignores this.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the vector doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multi_vector::{MultiVector, AutoBumpyEntry};
+    /// use std::ops::Range;
+    ///
+    /// struct MyEntryType { data: u32, index: usize, size: usize }
+    /// impl AutoBumpyEntry for MyEntryType {
+    ///     fn range(&self) -> Range<usize> { self.index..(self.index + self.size) }
+    /// }
+    ///
+    /// let mut mv: MultiVector<&str, MyEntryType> = MultiVector::new();
+    /// mv.create_vector("vector1", 100).unwrap();
+    /// mv.create_vector("vector2", 100).unwrap();
+    ///
+    /// mv.insert_entries(vec![
+    ///     (&"vector1", MyEntryType { data: 111, index: 0, size: 10 }),
+    ///     (&"vector2", MyEntryType { data: 222, index: 0, size: 10 }),
+    /// ]).unwrap();
+    ///
+    /// // Lock down the first vector
+    /// mv.freeze_vector(&"vector1").unwrap();
+    ///
+    /// // The group can't be removed from either side
+    /// assert!(mv.remove_entries(&"vector1", 0).is_err());
+    /// assert!(mv.remove_entries(&"vector2", 0).is_err());
+    ///
+    /// // Until it's thawed
+    /// mv.thaw_vector(&"vector1").unwrap();
+    /// assert_eq!(2, mv.remove_entries(&"vector2", 0).unwrap().len());
+    /// ```
+    pub fn freeze_vector(&mut self, vector: &N) -> SimpleResult<()> {
+        match self.vectors.get_mut(vector) {
+            Some(v) => v.frozen = true,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        Ok(())
+    }
+
+    /// Thaw a vector that was frozen with `freeze_vector()`, so its entries
+    /// can be changed again.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` on success, or `Err()` with a descriptive error
+    /// message if the vector doesn't exist.
+    pub fn thaw_vector(&mut self, vector: &N) -> SimpleResult<()> {
+        match self.vectors.get_mut(vector) {
+            Some(v) => v.frozen = false,
+            None => bail!("Couldn't find vector: {:?}", vector),
+        };
+
+        Ok(())
+    }
+
+    /// Returns whether the named vector is frozen (see `freeze_vector()`);
+    /// `false` if not found.
+    pub fn is_frozen(&self, vector: &N) -> bool {
+        match self.vectors.get(vector) {
+            Some(v) => v.frozen,
+            None => false,
+        }
+    }
+
+    /// Returns the total number of entries across all vectors.
+    pub fn len(&self) -> usize {
+        self.vectors.iter().map(|(_, v)| v.entries.len()).sum()
+    }
+}
+
+/// A predicate that a `Query` checks the entries' data with.
+type DataPredicate<'s, T> = Box<dyn Fn(&T) -> bool + 's>;
+
+/// A query over the entries in a `MultiVector`, created by
+/// `MultiVector::query()`.
+///
+/// The filters can be combined in any order, but they're always applied
+/// cheapest-first: the entries in the range are looked up in each vector,
+/// then the ones in groups of the wrong size are dropped, and only then are
+/// the data predicates called.
+pub struct Query<'s, N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    multi_vector: &'s MultiVector<N, T, M, G>,
+    vectors: Option<Vec<N>>,
+    range: Option<Range<u64>>,
+    group_size: (Bound<usize>, Bound<usize>),
+    data: Vec<DataPredicate<'s, T>>,
+}
+
+impl<'s, N, T, M, G> Query<'s, N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    /// Only return entries in these vectors (in this order - any that don't
+    /// exist are skipped).
+    pub fn vectors(mut self, vectors: &[N]) -> Self {
+        self.vectors = Some(vectors.to_vec());
+        self
+    }
+
+    /// Only return entries that overlap this range of indexes.
+    pub fn range(mut self, range: Range<u64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Only return entries whose group has this many entries in it (for
+    /// example, `2..` for entries that are linked to something).
+    pub fn group_size<R>(mut self, size: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let cloned = |bound: Bound<&usize>| match bound {
+            Bound::Included(n) => Bound::Included(*n),
+            Bound::Excluded(n) => Bound::Excluded(*n),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        self.group_size = (cloned(size.start_bound()), cloned(size.end_bound()));
+        self
+    }
+
+    /// Only return entries whose data matches `predicate`. If this is used
+    /// more than once, the entries have to match all of them.
+    pub fn data<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&T) -> bool + 's,
+    {
+        self.data.push(Box::new(predicate));
+        self
+    }
+}
+
+impl<'s, N, T, M, G> IntoIterator for Query<'s, N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    type Item = &'s BumpyEntry<MultiEntry<N, T>>;
+    type IntoIter = Box<dyn Iterator<Item = &'s BumpyEntry<MultiEntry<N, T>>> + 's>;
+
+    /// Run the query.
+    ///
+    /// The results are grouped by vector, and are in order within each
+    /// vector. They're computed as the iterator is used.
+    fn into_iter(self) -> Self::IntoIter {
+        let group_size = self.group_size;
+        let data = self.data;
+
+        Box::new(self.multi_vector._search(self.vectors.as_deref(), self.range)
+            .filter(move |e| group_size.contains(&e.entry.linked.len()))
+            .filter(move |e| data.iter().all(|predicate| predicate(&e.entry.data))))
+    }
+}
+
+/// Convert into an iterator.
+///
+/// Naively iterate across all entries, move them into a `Vec<_>`, and convert
+/// that vector into an iterator.
+///
+impl<'a, N, T, M, G> IntoIterator for &'a MultiVector<N, T, M, G>
+where
+    N: Hash + Eq + Debug + Clone,
+    T: AutoBumpyEntry,
+{
+    type Item = &'a BumpyEntry<MultiEntry<N, T>>;
+    type IntoIter = std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>>;
+
+    fn into_iter(self) -> std::vec::IntoIter<&'a BumpyEntry<MultiEntry<N, T>>> {
+        let mut result: Vec<&'a BumpyEntry<MultiEntry<N, T>>> = Vec::new();
+
+        for (_, v) in self.vectors.iter() {
+            for e in v.entries.into_iter() {
+                result.push(e);
+            }
+        }
+
+        result.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(feature = "serialize")]
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+    struct TestEntryType {
+        data: u32,
+        index: usize,
+        size: usize,
+    }
+
+    impl AutoBumpyEntry for TestEntryType {
+        fn range(&self) -> Range<usize> {
+            self.index..(self.index + self.size)
+        }
+    }
+
+    impl MovableEntry for TestEntryType {
+        fn move_to(&mut self, start: usize) {
+            self.index = start;
+        }
+    }
+
+    impl From<(u32, usize, usize)> for TestEntryType
+    {
+        fn from(o: (u32, usize, usize)) -> Self
+        {
+            TestEntryType {
+              data: o.0,
+              index: o.1,
+              size: o.2,
+            }
+        }
+    }
+
+    #[test]
+    fn test_create_and_destroy() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+
+        // No vectors to start with
+        assert_eq!(0, mv.vector_count());
+
+        // Create a 1000-element vector
+        mv.create_vector("name", 1000)?;
+        assert_eq!(1, mv.vector_count());
+
+        // Create a second vector
+        mv.create_vector("name2", 100)?;
+        assert_eq!(2, mv.vector_count());
+
+        // Destroy them
+        let removed_size = mv.destroy_vector(&"name")?;
+        assert_eq!(1000, removed_size);
+        assert_eq!(1, mv.vector_count());
+
+        let removed_size = mv.destroy_vector(&"name2")?;
+        assert_eq!(100, removed_size);
+        assert_eq!(0, mv.vector_count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_destroy_vector_fails_with_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+
+        // No vectors to start with
+        assert_eq!(0, mv.vector_count());
+
+        // Create a 1000-element vector
+        mv.create_vector("name", 1000)?;
+        assert_eq!(1, mv.vector_count());
+
+        // Create a second vector
+        mv.create_vector("name2", 100)?;
+        assert_eq!(2, mv.vector_count());
+
+        // Populate "name2"
+        mv.insert_entries(vec![
+            (&"name2", (123, 10, 10).into()),
+            (&"name2", (123, 20, 10).into()),
+        ])?;
+
+        // "name" is still empty, it can be destroyed
+        let removed_size = mv.destroy_vector(&"name")?;
+        assert_eq!(1000, removed_size);
+        assert_eq!(1, mv.vector_count());
+
+        // "name2" has an entry, so it can't be removed
+        assert!(mv.destroy_vector(&"name2").is_err());
+        assert_eq!(1, mv.vector_count());
+
+        // Remove the entries
+        mv.remove_entries(&"name2", 25)?;
+
+        // Try again
+        let removed_size = mv.destroy_vector(&"name2")?;
+        assert_eq!(100, removed_size);
+        assert_eq!(0, mv.vector_count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cant_have_same_vector_twice() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+
+        // No vectors to start with
+        assert_eq!(0, mv.vector_count());
+
+        // Create a 1000-element vector
+        mv.create_vector("name", 1000)?;
+        assert_eq!(1, mv.vector_count());
+
+        // Fail to create the same vector again
+        assert!(mv.create_vector("name", 100).is_err());
+        assert_eq!(1, mv.vector_count());
+
+        // Make sure it's still the original
+        assert_eq!(1000, mv.max_size_vector(&"name").unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        let entries = vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111,   0,  1).into()),
+            (&"vector1", (222,   5,  5).into()),
+            (&"vector1", (333,  10, 10).into()),
+
+            (&"vector2", (444,   0, 100).into()),
+            (&"vector2", (555, 100, 100).into()),
+        ];
+
+        // They are empty before
+        assert_eq!(0, mv.len_vector(&"vector1").unwrap());
+        assert_eq!(0, mv.len_vector(&"vector2").unwrap());
+
+        // Insert the entries
+        mv.insert_entries(entries)?;
+
+        // They are populated after
+        assert_eq!(3, mv.len_vector(&"vector1").unwrap());
+        assert_eq!(2, mv.len_vector(&"vector2").unwrap());
+
+        let more_entries = vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (667, 20, 1).into()),
+        ];
+
+        // Insert more entries
+        mv.insert_entries(more_entries)?;
+
+        // Make sure the vectors are still tracking
+        assert_eq!(4, mv.len_vector(&"vector1").unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_zero_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+
+        // Create no entries
+        let entries = vec![];
+
+        // Insert them entries
+        mv.insert_entries(entries)?;
+
+        // Ensure nothing was inserted.. I guess?
+        assert_eq!(0, mv.len_vector(&"vector1").unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_invalid_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        // Add a couple real entries so we can make sure we don't overwrite
+        // or remove them
+        mv.insert_entries(vec![
+            (&"vector1", (123,  0, 10).into()),
+            (&"vector1", (123, 10, 10).into()),
+            (&"vector1", (123, 20, 10).into()),
+            (&"vector2", (123,  0, 10).into()),
+        ])?;
+        assert_eq!(4, mv.len());
+
+        // Invalid vector
+        assert!(mv.insert_entries(vec![
+            (&"fakevector", (123, 0, 1).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        // Overlapping
+        assert!(mv.insert_entries(vec![
+            (&"vector1", (123, 0, 10).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        // Off the end
+        assert!(mv.insert_entries(vec![
+            (&"vector1", (123, 0, 1000).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        // Zero length
+        assert!(mv.insert_entries(vec![
+            (&"vector1", (123, 0, 0).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        // Overlapping each other
+        assert!(mv.insert_entries(vec![
+            (&"vector1", (123, 10, 10).into()),
+            (&"vector1", (123, 20, 10).into()),
+            (&"vector1", (123, 15,  1).into()),
+            (&"vector1", (123, 50, 10).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        // Multiple entries that overlap - this ensures that we don't
+        // accidentally remove things from the vector that we shouldn't
+        assert!(mv.insert_entries(vec![
+            (&"vector1", (123,  0, 10).into()),
+            (&"vector1", (123, 10, 10).into()),
+            (&"vector1", (123, 20, 10).into()),
+            (&"vector2", (123,  0, 10).into()),
+        ]).is_err());
+
+        // No entry should be added or removed
+        assert_eq!(4, mv.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_entry() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        // One group of entries
+        mv.insert_entries(vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111, 0,   1).into()),
+            (&"vector1", (222, 5,   5).into()),
+            (&"vector2", (444, 0, 100).into()),
+        ])?;
+
+        mv.insert_entries(vec![
+            (&"vector1", (333, 10, 10).into()),
+            (&"vector2", (555, 100, 100).into()),
+        ])?;
+
+        // Verify that all entries are there
+        assert_eq!(5, mv.len());
+
+        // Get a couple entries and make sure they're correct
+        assert_eq!(111, mv.get_entry(&"vector1",   0).unwrap().entry.data.data);
+        assert_eq!(222, mv.get_entry(&"vector1",   6).unwrap().entry.data.data);
+        assert_eq!(555, mv.get_entry(&"vector2", 115).unwrap().entry.data.data);
+
+        // Get some bad entries, make sure they're errors
+        assert!(mv.get_entry(&"badvector", 123).is_none());
+        assert!(mv.get_entry(&"vector1",  1000).is_none());
+        assert!(mv.get_entry(&"vector1",    50).is_none());
+
+        // Indexes are 64-bit, even ones that don't fit in a usize
+        assert!(mv.get_entry(&"vector1", u64::MAX).is_none());
+        assert!(mv.remove_entries(&"vector1", u64::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        // One group of entries
+        mv.insert_entries(vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111, 0,   1).into()),
+            (&"vector1", (222, 5,   5).into()),
+            (&"vector2", (444, 0, 100).into()),
+        ])?;
+
+        mv.insert_entries(vec![
+            (&"vector2", (555, 100, 100).into()),
+            (&"vector1", (333, 10, 10).into()),
+        ])?;
+
+        // Verify that all entries are there
+        assert_eq!(5, mv.len());
+
+        // Get the first entry at its start
+        let group1 = mv.get_entries(&"vector1", 0)?;
+        assert_eq!(3, group1.len());
+
+        assert_eq!(111, group1[0].unwrap().entry.data.data);
+        assert_eq!("vector1", group1[0].unwrap().entry.vector);
+
+        assert_eq!(222, group1[1].unwrap().entry.data.data);
+        assert_eq!("vector1", group1[1].unwrap().entry.vector);
+
+        assert_eq!(444, group1[2].unwrap().entry.data.data);
+        assert_eq!("vector2", group1[2].unwrap().entry.vector);
+
+        // Get the last entry (in the first group) in the middle
+        let group1 = mv.get_entries(&"vector2", 50)?;
+        assert_eq!(3, group1.len());
+
+        assert_eq!(111, group1[0].unwrap().entry.data.data);
+        assert_eq!("vector1", group1[0].unwrap().entry.vector);
+
+        assert_eq!(222, group1[1].unwrap().entry.data.data);
+        assert_eq!("vector1", group1[1].unwrap().entry.vector);
+
+        assert_eq!(444, group1[2].unwrap().entry.data.data);
+        assert_eq!("vector2", group1[2].unwrap().entry.vector);
+
+        // Get the second group
+        let group2 = mv.get_entries(&"vector2", 150)?;
+        assert_eq!(2, group2.len());
+
+        assert_eq!(555, group2[0].unwrap().entry.data.data);
+        assert_eq!("vector2", group2[0].unwrap().entry.vector);
+
+        assert_eq!(333, group2[1].unwrap().entry.data.data);
+        assert_eq!("vector1", group2[1].unwrap().entry.vector);
+
+        // Get some bad entries, make sure they're errors
+        assert!(mv.get_entries(&"badvector", 123).is_err());
+        assert!(mv.get_entries(&"vector1",  1000).is_err());
+        assert!(mv.get_entries(&"vector1",    50).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        // One group of entries
+        mv.insert_entries(vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111, 0,   1).into()),
+            (&"vector1", (222, 5,   5).into()),
+            (&"vector2", (444, 0, 100).into()),
+        ])?;
+
+        mv.insert_entries(vec![
+            (&"vector2", (555, 100, 100).into()),
+            (&"vector1", (333,  10,  10).into()),
+        ])?;
+
+        // Verify that all entries are there
+        assert_eq!(5, mv.len());
+
+        // Get the first entry at its start
+        let group1 = mv.remove_entries(&"vector1", 0)?;
+
+        // The group had 3 entries
+        assert_eq!(3, group1.len());
+
+        // Make sure they're actually removed
+        assert_eq!(2, mv.len());
+        assert!(mv.remove_entries(&"vector1", 0).is_err());
+
+        assert_eq!(111, group1[0].as_ref().unwrap().entry.data.data);
+        assert_eq!("vector1", group1[0].as_ref().unwrap().entry.vector);
+
+        assert_eq!(222, group1[1].as_ref().unwrap().entry.data.data);
+        assert_eq!("vector1", group1[1].as_ref().unwrap().entry.vector);
+
+        assert_eq!(444, group1[2].as_ref().unwrap().entry.data.data);
+        assert_eq!("vector2", group1[2].as_ref().unwrap().entry.vector);
+
+        // Get the second group
+        let group2 = mv.remove_entries(&"vector2", 150)?;
+        assert_eq!(2, group2.len());
+
+        assert_eq!(555, group2[0].as_ref().unwrap().entry.data.data);
+        assert_eq!("vector2", group2[0].as_ref().unwrap().entry.vector);
+
+        assert_eq!(333, group2[1].as_ref().unwrap().entry.data.data);
+        assert_eq!("vector1", group2[1].as_ref().unwrap().entry.vector);
+
+        // Get some bad entries, make sure they're errors
+        assert!(mv.remove_entries(&"badvector", 123).is_err());
+        assert!(mv.remove_entries(&"vector1",  1000).is_err());
+        assert!(mv.remove_entries(&"vector1",    50).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlink_entry() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector(&"vector1", 100)?;
+        mv.create_vector(&"vector2", 200)?;
+
+        // One group of entries
+        mv.insert_entries(vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111, 0,   1).into()),
+            (&"vector1", (222, 5,   5).into()),
+            (&"vector2", (444, 0, 100).into()), // Will be unlinked for test
+        ])?;
+
+        mv.insert_entries(vec![
+            (&"vector2", (555, 100, 100).into()), // Will be unlinked for test
+            (&"vector1", (333,  10,  10).into()),
+        ])?;
+
+        // Verify that all entries are there
+        assert_eq!(5, mv.len());
+
+        // Unlink a couple entries
+        mv.unlink_entry(&"vector2",  50)?;
+
+        mv.unlink_entry(&"vector2", 150)?;
+
+        // Test error conditions
+        assert!(mv.unlink_entry(&"badvector", 123).is_err());
+        assert!(mv.unlink_entry(&"vector1",  1000).is_err());
+        assert!(mv.unlink_entry(&"vector1",    50).is_err());
+
+        // Remove one
+        let removed = mv.remove_entries(&"vector2", 50)?;
+        assert_eq!(1, removed.len());
+        assert_eq!(4, mv.len());
+
+        // Remove the other
+        let removed = mv.remove_entries(&"vector2", 100)?;
+        assert_eq!(1, removed.len());
+        assert_eq!(3, mv.len());
+
+        // Remove the rest of the first group
+        let removed = mv.remove_entries(&"vector1", 0)?;
+        assert_eq!(2, removed.len());
+        assert_eq!(1, mv.len());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")] // Only test if we enable serialization
+    fn test_serialize() {
+        let mut mv: MultiVector<String, TestEntryType> = MultiVector::new();
+        mv.create_vector("myvector".to_string(), 20).unwrap();
+        mv.insert_entries(vec![
+            (&"myvector".to_string(), (111,  0, 10).into()),
+            (&"myvector".to_string(), (222, 10, 10).into()),
+        ]).unwrap();
+        assert_eq!(2, mv.len());
+
+        // Serialize
+        let serialized = ron::ser::to_string(&mv).unwrap();
+
+        // Deserialize
+        let mut mv: MultiVector<String, TestEntryType> = ron::de::from_str(&serialized).unwrap();
+        assert_eq!(2, mv.len());
+
+        // Make sure remove still works
+        assert_eq!(2, mv.remove_entries(&"myvector".to_string(), 0).unwrap().len());
+        assert_eq!(0, mv.len());
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("myvector", 20).unwrap();
+        mv.insert_entries(vec![
+            (&"myvector", (111,  0, 10).into()),
+            (&"myvector", (222, 10, 10).into()),
+        ]).unwrap();
+        assert_eq!(2, mv.len());
+
+        // Clone
+        let mut mv2 = mv.clone();
+        assert_eq!(2, mv2.len());
+
+        // Make sure remove still works
+        assert_eq!(2, mv2.remove_entries(&"myvector", 0).unwrap().len());
+        assert_eq!(0, mv2.len());
+
+        // Make sure the original is untouched
+        assert_eq!(2, mv.len());
+    }
+
+    #[test]
+    fn test_iterator() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        let entries = vec![
+            // (vector_name, ( data, index, length ) )
+            (&"vector1", (111,  0,    1).into()),
+            (&"vector1", (222,  5,    5).into()),
+            (&"vector1", (333, 10,   10).into()),
+
+            (&"vector2", (444, 0,   100).into()),
+            (&"vector2", (555, 100, 100).into()),
+        ];
+
+        // Insert the entries
+        mv.insert_entries(entries)?;
+
+        let mut i = mv.into_iter();
+        assert!(i.next().is_some());
+        assert!(i.next().is_some());
+        assert!(i.next().is_some());
+        assert!(i.next().is_some());
+        assert!(i.next().is_some());
+        assert!(i.next().is_none());
+
+        let mut i2 = mv.into_iter();
+        assert!(i2.next().is_some());
+        assert!(i2.next().is_some());
+        assert!(i2.next().is_some());
+        assert!(i2.next().is_some());
+        assert!(i2.next().is_some());
+        assert!(i2.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_string_keys() -> SimpleResult<()>{
+        let mut mv: MultiVector<(u8, &str), TestEntryType> = MultiVector::new();
+        mv.create_vector((1, "vector1"), 100)?;
+        mv.create_vector((2, "vector1"), 200)?;
+        mv.create_vector((1, "vector2"), 300)?;
+        mv.create_vector((2, "vector2"), 300)?;
+
+        let entries = vec![
+            // (vector_name, ( data, index, length ) )
+            (&(1, "vector1"), (111,  0,  1).into()),
+            (&(1, "vector1"), (222,  5,  5).into()),
+            (&(1, "vector1"), (333, 10, 10).into()),
+
+            (&(2, "vector1"), (444,  0,  1).into()),
+            (&(2, "vector1"), (555,  5,  5).into()),
+
+            (&(1, "vector2"), (777,  0,  1).into()),
+        ];
+
+        // They are empty before
+        assert_eq!(0, mv.len_vector(&(1, "vector1")).unwrap());
+        assert_eq!(0, mv.len_vector(&(2, "vector1")).unwrap());
+        assert_eq!(0, mv.len_vector(&(1, "vector2")).unwrap());
+        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+
+        // Insert the entries
+        mv.insert_entries(entries)?;
+
+        // They are populated after
+        assert_eq!(3, mv.len_vector(&(1, "vector1")).unwrap());
+        assert_eq!(2, mv.len_vector(&(2, "vector1")).unwrap());
+        assert_eq!(1, mv.len_vector(&(1, "vector2")).unwrap());
+        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+
+        // Unlink and remove a single entry
+        assert!(mv.unlink_entry(&(1, "vector1"), 0).is_ok());
+        assert!(mv.remove_entries(&(1, "vector1"), 0).is_ok());
+
+        // Make sure just the one went away
+        assert_eq!(2, mv.len_vector(&(1, "vector1")).unwrap());
+        assert_eq!(2, mv.len_vector(&(2, "vector1")).unwrap());
+        assert_eq!(1, mv.len_vector(&(1, "vector2")).unwrap());
+        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+
+        // Remove the entire rest of the group
+        assert!(mv.remove_entries(&(1, "vector2"), 0).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vector_meta() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (usize, &str)> = MultiVector::new();
+
+        // Create one vector with explicit metadata, and one with the default
+        mv.create_vector_with("vector1", 100, (0x1000, "file1"))?;
+        mv.create_vector("vector2", 200)?;
+
+        assert_eq!((0x1000, "file1"), *mv.vector_meta(&"vector1").unwrap());
+        assert_eq!((0, ""), *mv.vector_meta(&"vector2").unwrap());
+        assert!(mv.vector_meta(&"badvector").is_none());
+
+        // Can't re-use a name, and the original metadata is untouched
+        assert!(mv.create_vector_with("vector1", 100, (0x2000, "file2")).is_err());
+        assert_eq!((0x1000, "file1"), *mv.vector_meta(&"vector1").unwrap());
+
+        // Modify the metadata
+        mv.vector_meta_mut(&"vector2").unwrap().0 = 0x4000;
+        assert_eq!((0x4000, ""), *mv.vector_meta(&"vector2").unwrap());
+        assert!(mv.vector_meta_mut(&"badvector").is_none());
+
+        // The metadata comes back when the vector is destroyed
+        assert_eq!((100, (0x1000, "file1")), mv.destroy_vector_with(&"vector1")?);
+        assert!(mv.vector_meta(&"vector1").is_none());
+
+        // But not if the vector has entries
+        mv.insert_entry(&"vector2", (111, 0, 10).into())?;
+        assert!(mv.destroy_vector_with(&"vector2").is_err());
+        assert_eq!((0x4000, ""), *mv.vector_meta(&"vector2").unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")] // Only test if we enable serialization
+    fn test_serialize_vector_meta() {
+        let mut mv: MultiVector<String, TestEntryType, (u64, String)> = MultiVector::new();
+        mv.create_vector_with("myvector".to_string(), 20, (0x1000, "file1".to_string())).unwrap();
+
+        // Serialize
+        let serialized = ron::ser::to_string(&mv).unwrap();
+
+        // Deserialize
+        let mv: MultiVector<String, TestEntryType, (u64, String)> = ron::de::from_str(&serialized).unwrap();
+        assert_eq!((0x1000, "file1".to_string()), *mv.vector_meta(&"myvector".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_group_data() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), String> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 200)?;
+
+        // One group with data
+        mv.insert_entries_with(vec![
+            (&"vector1", (111, 0,   1).into()),
+            (&"vector1", (222, 5,   5).into()),
+            (&"vector2", (444, 0, 100).into()),
+        ], "group1".to_string())?;
+
+        // One group without
+        mv.insert_entries(vec![
+            (&"vector2", (555, 100, 100).into()),
+            (&"vector1", (333,  10,  10).into()),
+        ])?;
+
+        // Every member of the first group can get at the data
+        assert_eq!("group1", mv.group_data(&"vector1", 0).unwrap());
+        assert_eq!("group1", mv.group_data(&"vector1", 7).unwrap());
+        assert_eq!("group1", mv.group_data(&"vector2", 50).unwrap());
+
+        // The second group has none
+        assert!(mv.group_data(&"vector2", 150).is_none());
+        assert!(mv.group_data_mut(&"vector1", 10)?.is_none());
+        assert!(mv.group_data_mut(&"vector1", 50).is_err());
+
+        // Bad entries have none
+        assert!(mv.group_data(&"badvector", 0).is_none());
+        assert!(mv.group_data(&"vector1", 50).is_none());
+
+        // Modify the data through one member, and see it through another
+        mv.group_data_mut(&"vector1", 5)?.unwrap().push_str("modified");
+        assert_eq!("group1modified", mv.group_data(&"vector2", 0).unwrap());
+
+        // Add data to the second group
+        assert_eq!(None, mv.set_group_data(&"vector1", 10, Some("group2".to_string()))?);
+        assert_eq!("group2", mv.group_data(&"vector2", 100).unwrap());
+        assert!(mv.set_group_data(&"vector1", 50, None).is_err());
+
+        // Unlinking an entry leaves the data with the rest of the group
+        mv.unlink_entry(&"vector2", 0)?;
+        assert!(mv.group_data(&"vector2", 0).is_none());
+        assert_eq!("group1modified", mv.group_data(&"vector1", 0).unwrap());
+
+        // Removing the group returns the data
+        let (removed, data) = mv.remove_entries_with_data(&"vector1", 0)?;
+        assert_eq!(2, removed.len());
+        assert_eq!(Some("group1modified".to_string()), data);
+
+        // The unlinked entry has no data to return
+        let (removed, data) = mv.remove_entries_with_data(&"vector2", 0)?;
+        assert_eq!(1, removed.len());
+        assert_eq!(None, data);
+
+        // Plain old `remove_entries()` still works
+        assert_eq!(2, mv.remove_entries(&"vector1", 10)?.len());
+        assert_eq!(0, mv.len());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")] // Only test if we enable serialization
+    fn test_serialize_group_data() {
+        let mut mv: MultiVector<String, TestEntryType, (), String> = MultiVector::new();
+        mv.create_vector("myvector".to_string(), 20).unwrap();
+        mv.insert_entries_with(vec![
+            (&"myvector".to_string(), (111,  0, 10).into()),
+            (&"myvector".to_string(), (222, 10, 10).into()),
+        ], "mygroup".to_string()).unwrap();
+
+        // Serialize
+        let serialized = ron::ser::to_string(&mv).unwrap();
+
+        // Deserialize
+        let mut mv: MultiVector<String, TestEntryType, (), String> = ron::de::from_str(&serialized).unwrap();
+        assert_eq!("mygroup", mv.group_data(&"myvector".to_string(), 15).unwrap());
+
+        // Make sure the data comes back on removal
+        let (removed, data) = mv.remove_entries_with_data(&"myvector".to_string(), 0).unwrap();
+        assert_eq!(2, removed.len());
+        assert_eq!(Some("mygroup".to_string()), data);
+    }
+
+    #[test]
+    fn test_child_vectors() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("file", 1000)?;
+
+        // Create a couple children, and a grandchild
+        mv.create_child_vector(&"file", "section1", 100, 0..100)?;
+        mv.create_child_vector(&"file", "section2", 200, 100..300)?;
+        mv.create_child_vector(&"section2", "subsection", 50, 50..100)?;
+        assert_eq!(4, mv.vector_count());
+
+        // Bad parents, origins, and names
+        assert!(mv.create_child_vector(&"badvector", "child", 100, 0..100).is_err());
+        assert!(mv.create_child_vector(&"file", "child", 100, 0..1001).is_err());
+        assert!(mv.create_child_vector(&"file", "child", 100, 10..10).is_err());
+        assert!(mv.create_child_vector(&"file", "section1", 100, 0..100).is_err());
+        assert_eq!(4, mv.vector_count());
+
+        // Check the relationships
+        assert_eq!(None, mv.parent_of(&"file"));
+        assert_eq!(None, mv.parent_of(&"badvector"));
+        assert_eq!(Some((&"file", 0..100)), mv.parent_of(&"section1"));
+        assert_eq!(Some((&"file", 100..300)), mv.parent_of(&"section2"));
+        assert_eq!(Some((&"section2", 50..100)), mv.parent_of(&"subsection"));
+
+        let mut children = mv.children_of(&"file");
+        children.sort();
+        assert_eq!(vec![&"section1", &"section2"], children);
+        assert_eq!(vec![&"subsection"], mv.children_of(&"section2"));
+        assert!(mv.children_of(&"section1").is_empty());
+
+        // Can't destroy a vector with children
+        assert!(mv.destroy_vector(&"file").is_err());
+        assert!(mv.destroy_vector(&"section2").is_err());
+        assert_eq!(4, mv.vector_count());
+
+        // Destroy from the bottom up
+        assert_eq!(50, mv.destroy_vector(&"subsection")?);
+        assert_eq!(200, mv.destroy_vector(&"section2")?);
+        assert!(mv.destroy_vector(&"file").is_err());
+
+        // Forcing it orphans the child
+        assert!(mv.force_destroy_vector(&"file").is_some());
+        assert_eq!(None, mv.parent_of(&"section1"));
+        assert_eq!(1, mv.vector_count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_translate() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("file", 1000)?;
+        mv.create_child_vector(&"file", "archive", 500, 100..600)?;
+        mv.create_child_vector(&"archive", "member1", 100, 0..100)?;
+        mv.create_child_vector(&"archive", "member2", 100, 50..150)?;
+
+        // A child that's bigger than its origin (eg, decompressed)
+        mv.create_child_vector(&"file", "decompressed", 100, 900..910)?;
+
+        // One level
+        assert_eq!(Some(("file", 100)), mv.translate(&"archive", 0));
+        assert_eq!(Some(("file", 599)), mv.translate(&"archive", 499));
+        assert_eq!(Some(("archive", 60)), mv.translate(&"member2", 10));
+
+        // Multiple levels
+        assert_eq!(Some(("file", 160)), mv.translate_to_root(&"member2", 10));
+        assert_eq!(Some(("file", 100)), mv.translate_to_root(&"archive", 0));
+
+        // Things that can't be translated
+        assert_eq!(None, mv.translate(&"file", 0));
+        assert_eq!(None, mv.translate_to_root(&"file", 0));
+        assert_eq!(None, mv.translate(&"badvector", 0));
+        assert_eq!(None, mv.translate(&"archive", 500));
+        assert_eq!(None, mv.translate(&"decompressed", 10));
+        assert_eq!(Some(("file", 909)), mv.translate(&"decompressed", 9));
+
+        // Reverse
+        let mut reverse = mv.translate_reverse(&"file", 160);
+        reverse.sort();
+        assert_eq!(vec![("archive", 60), ("member1", 60), ("member2", 10)], reverse);
+
+        let mut reverse = mv.translate_reverse(&"archive", 120);
+        reverse.sort();
+        assert_eq!(vec![("member2", 70)], reverse);
+
+        assert_eq!(vec![("decompressed", 5)], mv.translate_reverse(&"file", 905));
+        assert!(mv.translate_reverse(&"file", 50).is_empty());
+        assert!(mv.translate_reverse(&"member1", 50).is_empty());
+        assert!(mv.translate_reverse(&"badvector", 50).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_address() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("image", 0x100)?;
+        mv.create_vector("plain", 0x100)?;
+
+        // Set the base address
+        assert_eq!(None, mv.base_address(&"image"));
+        mv.set_base_address(&"image", Some(0x1000))?;
+        assert_eq!(Some(0x1000), mv.base_address(&"image"));
+        assert!(mv.set_base_address(&"badvector", Some(0x1000)).is_err());
+
+        // Convert back and forth
+        assert_eq!(0x10, mv.address_to_index(&"image", 0x1010)?);
+        assert_eq!(0x1010, mv.index_to_address(&"image", 0x10)?);
+        assert_eq!(0x10, mv.address_to_index(&"plain", 0x10)?);
+        assert_eq!(0x10, mv.index_to_address(&"plain", 0x10)?);
+        assert!(mv.address_to_index(&"image", 0xfff).is_err());
+        assert!(mv.address_to_index(&"image", 0x1100).is_err());
+        assert!(mv.index_to_address(&"image", 0x100).is_err());
+        assert!(mv.address_to_index(&"badvector", 0x10).is_err());
+
+        // Base addresses can be above 4GiB on any target
+        mv.create_vector("high", 0x100)?;
+        mv.set_base_address(&"high", Some(0x1_0000_0000))?;
+        assert_eq!(0x10, mv.address_to_index(&"high", 0x1_0000_0010)?);
+        assert_eq!(0x1_0000_0010, mv.index_to_address(&"high", 0x10)?);
+        mv.destroy_vector(&"high")?;
+
+        // Insert a group spanning both vectors - addresses in one, indexes in
+        // the other
+        mv.insert_entries(vec![
+            (&"image", (111, 0x1000, 0x10).into()),
+            (&"image", (222, 0x1010, 0x10).into()),
+            (&"plain", (333,   0x10, 0x10).into()),
+        ])?;
+        assert_eq!(3, mv.len());
+
+        // Links are by index
+        let linked = &mv.get_entry(&"image", 0x15).unwrap().entry.linked;
+        assert_eq!(vec![("image", 0x00), ("image", 0x10), ("plain", 0x10)], *linked);
+
+        // Can't change the base now
+        assert!(mv.set_base_address(&"image", Some(0x2000)).is_err());
+        assert_eq!(Some(0x1000), mv.base_address(&"image"));
+
+        // Look them up by address
+        assert_eq!(111, mv.get_entry_at_address(&"image", 0x1000).unwrap().entry.data.data);
+        assert_eq!(222, mv.get_entry_at_address(&"image", 0x101f).unwrap().entry.data.data);
+        assert!(mv.get_entry_at_address(&"image", 0x1020).is_none());
+        assert!(mv.get_entry_at_address(&"image", 0x10).is_none());
+        assert!(mv.get_entry_at_address(&"image", 0x2000).is_none());
+
+        // Range queries
+        let range = mv.get_range_at_address(&"image", 0x1008..0x1011)?;
+        assert_eq!(2, range.len());
+        assert_eq!(111, range[0].entry.data.data);
+        assert_eq!(222, range[1].entry.data.data);
+        assert_eq!(1, mv.get_range_at_address(&"image", 0x1010..0x1100)?.len());
+        assert!(mv.get_range_at_address(&"image", 0x0..0x10).is_err());
+        assert!(mv.get_range_at_address(&"image", 0x1000..0x1101).is_err());
+        assert_eq!(2, mv.get_range(&"image", 0x0..0x100)?.len());
+        assert!(mv.get_range(&"badvector", 0x0..0x100).is_err());
+
+        // Addresses outside the vector can't be inserted, and nothing is
+        // changed
+        assert!(mv.insert_entries(vec![
+            (&"image", (444, 0x1020, 0x10).into()),
+            (&"image", (555,    0x0, 0x10).into()),
+        ]).is_err());
+        assert!(mv.insert_entry(&"image", (444, 0x10f0, 0x11).into()).is_err());
+        assert_eq!(3, mv.len());
+
+        // Remove the group, then the base can be changed
+        assert_eq!(3, mv.remove_entries(&"image", 0)?.len());
+        mv.set_base_address(&"image", None)?;
+        assert_eq!(None, mv.base_address(&"image"));
+
+        // The whole vector has to fit below u64::MAX
+        assert!(mv.set_base_address(&"image", Some(u64::MAX - 0xff)).is_err());
+        assert_eq!(None, mv.base_address(&"image"));
+        mv.set_base_address(&"image", Some(u64::MAX - 0x100))?;
+        assert_eq!(u64::MAX - 1, mv.index_to_address(&"image", 0xff)?);
+        assert_eq!(0xff, mv.address_to_index(&"image", u64::MAX - 1)?);
+        assert!(mv.address_to_index(&"image", u64::MAX).is_err());
+        assert!(mv.insert_space(&"image", 0x100, 1).is_err());
+
+        Ok(())
+    }
+
+    // The entries still use usize ranges, so this needs a 64-bit target
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_large_vector() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+
+        // Bigger than a 32-bit index can reach
+        mv.create_vector("vector", 1 << 34)?;
+        assert_eq!(Some(1 << 34), mv.max_size_vector(&"vector"));
+
+        // Entries can live (and link to each other) above 4GiB
+        mv.insert_entries(vec![
+            (&"vector", (111,  1 << 33,         0x10).into()),
+            (&"vector", (222, (1 << 33) + 0x10, 0x10).into()),
+        ])?;
+        assert_eq!(111, mv.get_entry(&"vector", (1 << 33) + 0xf).unwrap().entry.data.data);
+        assert_eq!(222, mv.get_entry(&"vector", (1 << 33) + 0x10).unwrap().entry.data.data);
+
+        let linked = &mv.get_entry(&"vector", 1 << 33).unwrap().entry.linked;
+        assert_eq!(vec![("vector", 1 << 33), ("vector", (1 << 33) + 0x10)], *linked);
+
+        // Nothing fits past the end
+        assert!(mv.insert_entry(&"vector", (333, (1 << 34) - 0x8, 0x10).into()).is_err());
+
+        // And the group is removed by its 64-bit index
+        assert_eq!(2, mv.remove_entries(&"vector", (1 << 33) + 0x10)?.len());
+        assert_eq!(0, mv.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_space() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+        mv.create_child_vector(&"vector1", "child1", 10, 30..40)?;
+        mv.create_child_vector(&"vector1", "child2", 10, 0..10)?;
+
+        mv.insert_entries(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 20, 10).into()),
+            (&"vector2", (333, 20, 10).into()),
+        ])?;
+        mv.insert_entry(&"vector1", (444, 50, 10).into())?;
 
-        let removed_size = mv.destroy_vector(&"name2")?;
-        assert_eq!(100, removed_size);
-        assert_eq!(0, mv.vector_count());
+        // Insert space between the first two entries
+        mv.insert_space(&"vector1", 15, 5)?;
+        assert_eq!(105, mv.max_size_vector(&"vector1").unwrap());
+        assert_eq!(100, mv.max_size_vector(&"vector2").unwrap());
+        assert_eq!(4, mv.len());
+
+        // Check where everything is now
+        assert_eq!(0,  mv.get_entry(&"vector1",  0).unwrap().entry.data.index);
+        assert_eq!(25, mv.get_entry(&"vector1", 25).unwrap().entry.data.index);
+        assert_eq!(25..35, mv.get_entry(&"vector1", 25).unwrap().range);
+        assert_eq!(55, mv.get_entry(&"vector1", 55).unwrap().entry.data.index);
+        assert_eq!(20, mv.get_entry(&"vector2", 20).unwrap().entry.data.index);
+        assert!(mv.get_entry(&"vector1", 20).is_none());
+
+        // The links were fixed, even from the other vector
+        let expected = vec![("vector1", 0), ("vector1", 25), ("vector2", 20)];
+        assert_eq!(expected, mv.get_entry(&"vector1", 0).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"vector1", 25).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"vector2", 20).unwrap().entry.linked);
+        assert_eq!(vec![("vector1", 55)], mv.get_entry(&"vector1", 55).unwrap().entry.linked);
+
+        // The child origins moved too
+        assert_eq!(Some((&"vector1", 35..45)), mv.parent_of(&"child1"));
+        assert_eq!(Some((&"vector1", 0..10)), mv.parent_of(&"child2"));
+
+        // Insert at the very end
+        mv.insert_space(&"vector1", 105, 5)?;
+        assert_eq!(110, mv.max_size_vector(&"vector1").unwrap());
+
+        // Bad insertions - inside an entry, inside an origin, off the end
+        assert!(mv.insert_space(&"vector1", 27, 5).is_err());
+        assert!(mv.insert_space(&"vector1", 40, 5).is_err());
+        assert!(mv.insert_space(&"vector1", 111, 5).is_err());
+        assert!(mv.insert_space(&"badvector", 0, 5).is_err());
+        assert_eq!(110, mv.max_size_vector(&"vector1").unwrap());
+
+        // Removing through the moved links still removes the whole group
+        assert_eq!(3, mv.remove_entries(&"vector2", 20)?.len());
+        assert_eq!(1, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_destroy_vector_fails_with_entries() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn test_delete_space() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+        mv.create_child_vector(&"vector1", "child", 10, 80..90)?;
+
+        mv.insert_entries_with(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 50, 10).into()),
+            (&"vector2", (333, 20, 10).into()),
+        ], "group1")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (444, 20, 5).into()),
+            (&"vector2", (555, 50, 5).into()),
+        ], "group2")?;
+        assert_eq!(5, mv.len());
 
-        // No vectors to start with
-        assert_eq!(0, mv.vector_count());
+        // Can't delete part of an entry or an origin, and nothing changes
+        assert!(mv.delete_space(&"vector1", 5..15).is_err());
+        assert!(mv.delete_space(&"vector1", 75..85).is_err());
+        assert!(mv.delete_space(&"vector1", 95..101).is_err());
+        assert!(mv.delete_space(&"vector1", 20..20).is_err());
+        assert!(mv.delete_space(&"badvector", 20..30).is_err());
+        assert_eq!(100, mv.max_size_vector(&"vector1").unwrap());
+        assert_eq!(5, mv.len());
 
-        // Create a 1000-element vector
-        mv.create_vector("name", 1000)?;
-        assert_eq!(1, mv.vector_count());
+        // Delete some empty space
+        assert_eq!(0, mv.delete_space(&"vector1", 10..20)?.len());
+        assert_eq!(90, mv.max_size_vector(&"vector1").unwrap());
+        assert_eq!(5, mv.len());
+        assert_eq!(10, mv.get_entry(&"vector1", 10).unwrap().entry.data.index);
+        assert_eq!(40, mv.get_entry(&"vector1", 40).unwrap().entry.data.index);
+        assert_eq!(Some((&"vector1", 70..80)), mv.parent_of(&"child"));
 
-        // Create a second vector
-        mv.create_vector("name2", 100)?;
-        assert_eq!(2, mv.vector_count());
+        let expected = vec![("vector1", 0), ("vector1", 40), ("vector2", 20)];
+        assert_eq!(expected, mv.get_entry(&"vector2", 20).unwrap().entry.linked);
+        let expected = vec![("vector1", 10), ("vector2", 50)];
+        assert_eq!(expected, mv.get_entry(&"vector2", 50).unwrap().entry.linked);
 
-        // Populate "name2"
+        // Delete space containing an entry - the whole group goes
+        let removed = mv.delete_space(&"vector1", 10..20)?;
+        assert_eq!(1, removed.len());
+        assert_eq!(2, removed[0].entries.len());
+        assert_eq!(Some("group2"), removed[0].data);
+        assert_eq!(80, mv.max_size_vector(&"vector1").unwrap());
+        assert_eq!(3, mv.len());
+        assert!(mv.get_entry(&"vector2", 50).is_none());
+
+        // The other group moved back again
+        let expected = vec![("vector1", 0), ("vector1", 30), ("vector2", 20)];
+        assert_eq!(expected, mv.get_entry(&"vector1", 30).unwrap().entry.linked);
+        assert_eq!(Some((&"vector1", 60..70)), mv.parent_of(&"child"));
+        assert_eq!("group1", *mv.group_data(&"vector1", 30).unwrap());
+
+        // If what's left can't be moved, nothing is removed either
+        mv.create_vector_with_options("aligned", 100, (), VectorOptions { alignment: Some(4), ..Default::default() })?;
+        mv.insert_entry(&"aligned", (666, 0, 4).into())?;
+        mv.insert_entry(&"aligned", (777, 8, 4).into())?;
+        assert!(mv.delete_space(&"aligned", 0..6).is_err());
+        assert_eq!(666, mv.get_entry(&"aligned", 0).unwrap().entry.data.data);
+        assert_eq!(777, mv.get_entry(&"aligned", 8).unwrap().entry.data.data);
+
+        // But an entry that's removed with its group doesn't have to fit
+        mv.remove_entries(&"aligned", 0)?;
+        mv.remove_entries(&"aligned", 8)?;
         mv.insert_entries(vec![
-            (&"name2", (123, 10, 10).into()),
-            (&"name2", (123, 20, 10).into()),
+            (&"aligned", (666, 0, 4).into()),
+            (&"aligned", (777, 8, 4).into()),
         ])?;
+        assert_eq!(1, mv.delete_space(&"aligned", 0..6)?.len());
+        assert_eq!(94, mv.max_size_vector(&"aligned").unwrap());
+        assert!(mv.get_entry(&"aligned", 2).is_none());
 
-        // "name" is still empty, it can be destroyed
-        let removed_size = mv.destroy_vector(&"name")?;
-        assert_eq!(1000, removed_size);
-        assert_eq!(1, mv.vector_count());
-
-        // "name2" has an entry, so it can't be removed
-        assert!(mv.destroy_vector(&"name2").is_err());
-        assert_eq!(1, mv.vector_count());
-
-        // Remove the entries
-        mv.remove_entries(&"name2", 25)?;
+        Ok(())
+    }
 
-        // Try again
-        let removed_size = mv.destroy_vector(&"name2")?;
-        assert_eq!(100, removed_size);
-        assert_eq!(0, mv.vector_count());
+    #[test]
+    fn test_rebase_vector() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+        mv.create_child_vector(&"vector1", "child", 10, 50..60)?;
+
+        mv.insert_entries_with(vec![
+            (&"vector1", (111, 10, 10).into()),
+            (&"vector1", (222, 20, 10).into()),
+            (&"vector2", (333, 20, 10).into()),
+        ], "group1")?;
+        mv.insert_entry(&"vector1", (444, 80, 10).into())?;
+
+        // Move forward
+        mv.rebase_vector(&"vector1", 5)?;
+        assert_eq!(15, mv.get_entry(&"vector1", 15).unwrap().entry.data.index);
+        assert_eq!(25, mv.get_entry(&"vector1", 25).unwrap().entry.data.index);
+        assert_eq!(85, mv.get_entry(&"vector1", 85).unwrap().entry.data.index);
+        assert!(mv.get_entry(&"vector1", 10).is_none());
+
+        // Links (including from other vectors) follow
+        let expected = vec![("vector1", 15), ("vector1", 25), ("vector2", 20)];
+        assert_eq!(expected, mv.get_entry(&"vector2", 20).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"vector1", 15).unwrap().entry.linked);
+        assert_eq!("group1", *mv.group_data(&"vector2", 20).unwrap());
+
+        // The size and children don't change
+        assert_eq!(100, mv.max_size_vector(&"vector1").unwrap());
+        assert_eq!(Some((&"vector1", 50..60)), mv.parent_of(&"child"));
+
+        // Move backward
+        mv.rebase_vector(&"vector1", -15)?;
+        assert_eq!(0, mv.get_entry(&"vector1", 0).unwrap().entry.data.index);
+        let expected = vec![("vector1", 0), ("vector1", 10), ("vector2", 20)];
+        assert_eq!(expected, mv.get_entry(&"vector2", 20).unwrap().entry.linked);
+
+        // Off either end, or a bad vector
+        assert!(mv.rebase_vector(&"vector1", -1).is_err());
+        assert!(mv.rebase_vector(&"vector1", 31).is_err());
+        assert!(mv.rebase_vector(&"badvector", 1).is_err());
+        assert_eq!(0, mv.get_entry(&"vector1", 0).unwrap().entry.data.index);
+        assert_eq!(70, mv.get_entry(&"vector1", 70).unwrap().entry.data.index);
+
+        // Exactly to the end is fine
+        mv.rebase_vector(&"vector1", 20)?;
+        assert_eq!(90..100, mv.get_entry(&"vector1", 99).unwrap().range);
+
+        // Removing still removes the whole group
+        assert_eq!(3, mv.remove_entries(&"vector1", 20)?.len());
+        assert_eq!(1, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_cant_have_same_vector_twice() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn test_remove_range() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+
+        mv.insert_entries_with(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 10).into()),
+            (&"vector2", (333,  0, 10).into()),
+        ], "group1")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (444, 30, 10).into()),
+        ], "group2")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (555, 50, 10).into()),
+            (&"vector2", (666, 50, 10).into()),
+        ], "group3")?;
+        assert_eq!(6, mv.len());
+
+        // Nothing in range
+        assert_eq!(0, mv.remove_range(&"vector1", 20..30)?.len());
+        assert_eq!(0, mv.remove_range(&"vector1", 90..1000)?.len());
+        assert!(mv.remove_range(&"badvector", 0..100).is_err());
+        assert_eq!(6, mv.len());
+
+        // Partially overlap two members of the same group, and one other group
+        let removed = mv.remove_range(&"vector1", 5..35)?;
+        assert_eq!(2, removed.len());
+        assert_eq!(3, removed[0].entries.len());
+        assert_eq!(Some("group1"), removed[0].data);
+        assert_eq!(1, removed[1].entries.len());
+        assert_eq!(Some("group2"), removed[1].data);
 
-        // No vectors to start with
-        assert_eq!(0, mv.vector_count());
+        // Including the member in the other vector
+        assert_eq!(2, mv.len());
+        assert!(mv.get_entry(&"vector2", 0).is_none());
 
-        // Create a 1000-element vector
-        mv.create_vector("name", 1000)?;
-        assert_eq!(1, mv.vector_count());
+        // Put them back
+        mv.restore_groups(removed)?;
+        assert_eq!(6, mv.len());
+        assert_eq!("group1", *mv.group_data(&"vector2", 0).unwrap());
+        assert_eq!("group2", *mv.group_data(&"vector1", 30).unwrap());
 
-        // Fail to create the same vector again
-        assert!(mv.create_vector("name", 100).is_err());
-        assert_eq!(1, mv.vector_count());
+        // They're still grouped, in the same order
+        let expected = vec![("vector1", 0), ("vector1", 10), ("vector2", 0)];
+        assert_eq!(expected, mv.get_entry(&"vector1", 10).unwrap().entry.linked);
 
-        // Make sure it's still the original
-        assert_eq!(1000, mv.max_size_vector(&"name").unwrap());
+        // Restoring on top of existing entries fails, and backs out
+        let removed = mv.remove_range(&"vector1", 0..100)?;
+        assert_eq!(3, removed.len());
+        assert_eq!(0, mv.len());
+        mv.insert_entry(&"vector2", (777, 55, 1).into())?;
+        assert!(mv.restore_groups(removed).is_err());
+        assert_eq!(1, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_insert_entries() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
-        mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
+    fn test_destroy_vector_cascade() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, &str, &str> = MultiVector::new();
+        mv.create_vector_with("vector1", 100, "meta1")?;
+        mv.create_vector_with("vector2", 100, "meta2")?;
+        mv.create_vector_with("vector3", 100, "meta3")?;
+        mv.set_base_address(&"vector1", Some(0x1000))?;
+
+        mv.insert_entries_with(vec![
+            (&"vector1", (111, 0x1000, 10).into()),
+            (&"vector2", (222,      0, 10).into()),
+        ], "group1")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (333, 0x1020, 10).into()),
+        ], "group2")?;
+        mv.insert_entries_with(vec![
+            (&"vector2", (444, 20, 10).into()),
+            (&"vector3", (555, 20, 10).into()),
+        ], "group3")?;
+        assert_eq!(5, mv.len());
 
-        let entries = vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111,   0,  1).into()),
-            (&"vector1", (222,   5,  5).into()),
-            (&"vector1", (333,  10, 10).into()),
+        // Can't destroy a parent, or a vector that doesn't exist
+        mv.create_child_vector(&"vector3", "child", 10, 0..10)?;
+        assert!(mv.destroy_vector_cascade(&"vector3").is_err());
+        assert!(mv.destroy_vector_cascade(&"badvector").is_err());
+        assert_eq!(5, mv.len());
 
-            (&"vector2", (444,   0, 100).into()),
-            (&"vector2", (555, 100, 100).into()),
-        ];
+        // Destroy vector1, which takes part of vector2 with it
+        let destroyed = mv.destroy_vector_cascade(&"vector1")?;
+        assert_eq!("vector1", destroyed.name);
+        assert_eq!(2, destroyed.groups.len());
+        assert!(!mv.vector_exists(&"vector1"));
+        assert_eq!(2, mv.len());
+        assert!(mv.get_entry(&"vector2", 0).is_none());
 
-        // They are empty before
-        assert_eq!(0, mv.len_vector(&"vector1").unwrap());
-        assert_eq!(0, mv.len_vector(&"vector2").unwrap());
+        // Can't restore it while the name is in use
+        mv.create_vector("vector1", 10)?;
+        assert!(mv.restore_vector(destroyed.clone()).is_err());
+        mv.destroy_vector(&"vector1")?;
 
-        // Insert the entries
-        mv.insert_entries(entries)?;
+        // Can't restore it if something is in the way, and nothing changes
+        mv.insert_entry(&"vector2", (666, 5, 1).into())?;
+        assert!(mv.restore_vector(destroyed.clone()).is_err());
+        assert!(!mv.vector_exists(&"vector1"));
+        assert_eq!(3, mv.len());
+        mv.remove_entries(&"vector2", 5)?;
 
-        // They are populated after
-        assert_eq!(3, mv.len_vector(&"vector1").unwrap());
-        assert_eq!(2, mv.len_vector(&"vector2").unwrap());
+        // Restore it properly
+        mv.restore_vector(destroyed)?;
+        assert_eq!(5, mv.len());
+        assert_eq!("meta1", *mv.vector_meta(&"vector1").unwrap());
+        assert_eq!(Some(0x1000), mv.base_address(&"vector1"));
+        assert_eq!("group1", *mv.group_data(&"vector2", 0).unwrap());
+        assert_eq!("group2", *mv.group_data(&"vector1", 0x20).unwrap());
+        assert_eq!(2, mv.get_entries(&"vector2", 0)?.len());
+
+        // Destroy a child, and its parent relationship comes back too
+        let destroyed = mv.destroy_vector_cascade(&"child")?;
+        assert!(mv.children_of(&"vector3").is_empty());
+        mv.restore_vector(destroyed)?;
+        assert_eq!(Some((&"vector3", 0..10)), mv.parent_of(&"child"));
 
-        let more_entries = vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (667, 20, 1).into()),
-        ];
+        Ok(())
+    }
 
-        // Insert more entries
-        mv.insert_entries(more_entries)?;
+    #[test]
+    fn test_duplicate_vector() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, &str, &str> = MultiVector::new();
+        mv.create_vector_with("vector1", 100, "meta1")?;
+        mv.create_vector("other", 100)?;
+
+        mv.insert_entries_with(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 10).into()),
+        ], "local")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (333, 50, 10).into()),
+            (&"other",   (444,  0, 10).into()),
+        ], "cross")?;
+        mv.insert_entry(&"vector1", (555, 80, 10).into())?;
+        assert_eq!(5, mv.len());
 
-        // Make sure the vectors are still tracking
-        assert_eq!(4, mv.len_vector(&"vector1").unwrap());
+        // Bad source, name in use, or entries that don't fit
+        assert!(mv.duplicate_vector(&"badvector", "copy", Some, CrossVectorLinks::Skip).is_err());
+        assert!(mv.duplicate_vector(&"vector1", "other", Some, CrossVectorLinks::Skip).is_err());
+        assert!(mv.duplicate_vector(&"vector1", "copy", |i| Some(i + 20), CrossVectorLinks::Skip).is_err());
+        assert!(mv.duplicate_vector(&"vector1", "copy", |_| Some(0), CrossVectorLinks::Skip).is_err());
+        assert!(mv.duplicate_vector(&"vector1", "copy", |_| Some(u64::MAX - 2), CrossVectorLinks::Skip).unwrap_err().to_string().contains("past the largest possible index"));
+        assert!(!mv.vector_exists(&"copy"));
+        assert_eq!(5, mv.len());
+
+        // Skip cross-vector members, and leave out the last entry
+        let report = mv.duplicate_vector(&"vector1", "copy1", |i| if i < 80 { Some(i + 5) } else { None }, CrossVectorLinks::Skip)?;
+        assert_eq!(DuplicateReport {
+            entries_copied: 3,
+            entries_skipped: 1,
+            groups_created: 2,
+            groups_linked: 0,
+            members_skipped: 1,
+        }, report);
+        assert_eq!(8, mv.len());
+        assert_eq!(100, mv.max_size_vector(&"copy1").unwrap());
+        assert_eq!("meta1", *mv.vector_meta(&"copy1").unwrap());
+
+        // The copies moved, and are grouped with each other
+        assert_eq!(15, mv.get_entry(&"copy1", 15).unwrap().entry.data.index);
+        assert_eq!(vec![("copy1", 5), ("copy1", 15)], mv.get_entry(&"copy1", 5).unwrap().entry.linked);
+        assert_eq!("local", *mv.group_data(&"copy1", 5).unwrap());
+        assert_eq!(vec![("copy1", 55)], mv.get_entry(&"copy1", 55).unwrap().entry.linked);
+        assert_eq!("cross", *mv.group_data(&"copy1", 55).unwrap());
+        assert!(mv.get_entry(&"copy1", 85).is_none());
+
+        // The originals are untouched
+        assert_eq!(2, mv.get_entries(&"other", 0)?.len());
+
+        // Now link the cross-vector group
+        let report = mv.duplicate_vector(&"vector1", "copy2", Some, CrossVectorLinks::Link)?;
+        assert_eq!(DuplicateReport {
+            entries_copied: 4,
+            entries_skipped: 0,
+            groups_created: 2,
+            groups_linked: 1,
+            members_skipped: 0,
+        }, report);
+        assert_eq!(12, mv.len());
+
+        // The copy joined the original group
+        let expected = vec![("vector1", 50), ("other", 0), ("copy2", 50)];
+        assert_eq!(expected, mv.get_entry(&"other", 0).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"copy2", 50).unwrap().entry.linked);
+        assert_eq!("cross", *mv.group_data(&"copy2", 50).unwrap());
+
+        // So removing it removes all three
+        assert_eq!(3, mv.remove_entries(&"copy2", 50)?.len());
+        assert_eq!(9, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_insert_zero_entries() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn test_split_entry() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
         mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
 
-        // Create no entries
-        let entries = vec![];
+        mv.insert_entries_with(vec![
+            (&"vector1", (111,  0,  4).into()),
+            (&"vector1", (222,  4, 16).into()),
+            (&"vector2", (333,  0, 10).into()),
+        ], "mystruct")?;
+        assert_eq!(3, mv.len());
 
-        // Insert them entries
-        mv.insert_entries(entries)?;
+        let splitter = |blob: TestEntryType| -> Vec<TestEntryType> {
+            (0..4).map(|i| (blob.data + i as u32, blob.index + (i * 4), 4).into()).collect()
+        };
 
-        // Ensure nothing was inserted.. I guess?
-        assert_eq!(0, mv.len_vector(&"vector1").unwrap());
+        // Bad split points
+        assert!(mv.split_entry(&"vector1", 4, &[4, 8, 12], splitter).is_err());
+        assert!(mv.split_entry(&"vector1", 4, &[8, 12, 20], splitter).is_err());
+        assert!(mv.split_entry(&"vector1", 4, &[12, 8, 16], splitter).is_err());
+        assert!(mv.split_entry(&"vector1", 50, &[52], splitter).is_err());
+        assert!(mv.split_entry(&"badvector", 4, &[8], splitter).is_err());
+
+        // Pieces that don't line up with the split points
+        assert!(mv.split_entry(&"vector1", 4, &[8, 12], splitter).is_err());
+        assert!(mv.split_entry(&"vector1", 4, &[6, 12, 16], splitter).is_err());
+
+        // Nothing changed
+        assert_eq!(3, mv.len());
+        assert_eq!(4..20, mv.get_entry(&"vector1", 4).unwrap().range);
+
+        // Split it properly, from an index in the middle
+        mv.split_entry(&"vector1", 10, &[8, 12, 16], splitter)?;
+        assert_eq!(6, mv.len());
+        assert_eq!(222, mv.get_entry(&"vector1",  4).unwrap().entry.data.data);
+        assert_eq!(223, mv.get_entry(&"vector1",  8).unwrap().entry.data.data);
+        assert_eq!(224, mv.get_entry(&"vector1", 12).unwrap().entry.data.data);
+        assert_eq!(225, mv.get_entry(&"vector1", 19).unwrap().entry.data.data);
+        assert_eq!(16..20, mv.get_entry(&"vector1", 19).unwrap().range);
+
+        // The pieces took the original's place in the group
+        let expected = vec![
+            ("vector1", 0),
+            ("vector1", 4), ("vector1", 8), ("vector1", 12), ("vector1", 16),
+            ("vector2", 0),
+        ];
+        assert_eq!(expected, mv.get_entry(&"vector2", 0).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"vector1", 12).unwrap().entry.linked);
+        assert_eq!("mystruct", *mv.group_data(&"vector1", 16).unwrap());
+
+        // Removing a piece removes the whole group
+        assert_eq!(6, mv.remove_entries(&"vector1", 8)?.len());
+        assert_eq!(0, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_insert_invalid_entries() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn test_merge_entries() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
         mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
+        mv.create_vector("vector2", 100)?;
+
+        // Three groups, one of which has a member in another vector
+        mv.insert_entries_with(vec![
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector1", (222, 4, 4).into()),
+        ], "group1")?;
+        mv.insert_entries_with(vec![
+            (&"vector2", (333, 0, 4).into()),
+            (&"vector1", (444, 8, 4).into()),
+        ], "group2")?;
+        mv.insert_entries_with(vec![
+            (&"vector1", (555, 20, 4).into()),
+        ], "group3")?;
+        assert_eq!(5, mv.len());
 
-        // Add a couple real entries so we can make sure we don't overwrite
-        // or remove them
-        mv.insert_entries(vec![
-            (&"vector1", (123,  0, 10).into()),
-            (&"vector1", (123, 10, 10).into()),
-            (&"vector1", (123, 20, 10).into()),
-            (&"vector2", (123,  0, 10).into()),
-        ])?;
-        assert_eq!(4, mv.len());
+        let combiner = |entries: Vec<TestEntryType>| -> TestEntryType {
+            let size = entries.iter().map(|e| e.size).sum();
+            (entries.iter().map(|e| e.data).sum(), entries[0].index, size).into()
+        };
 
-        // Invalid vector
-        assert!(mv.insert_entries(vec![
-            (&"fakevector", (123, 0, 1).into()),
-        ]).is_err());
+        // Not contiguous, straddling, empty, or a bad vector
+        assert!(mv.merge_entries(&"vector1", 0..24, combiner).is_err());
+        assert!(mv.merge_entries(&"vector1", 2..12, combiner).is_err());
+        assert!(mv.merge_entries(&"vector1", 0..10, combiner).is_err());
+        assert!(mv.merge_entries(&"vector1", 0..14, combiner).is_err());
+        assert!(mv.merge_entries(&"vector1", 12..16, combiner).is_err());
+        assert!(mv.merge_entries(&"badvector", 0..12, combiner).is_err());
 
-        // No entry should be added or removed
-        assert_eq!(4, mv.len());
+        // A combiner that gets the range wrong
+        assert!(mv.merge_entries(&"vector1", 0..12, |_| (0, 0, 11).into()).is_err());
 
-        // Overlapping
-        assert!(mv.insert_entries(vec![
-            (&"vector1", (123, 0, 10).into()),
-        ]).is_err());
+        // Nothing changed
+        assert_eq!(5, mv.len());
 
-        // No entry should be added or removed
-        assert_eq!(4, mv.len());
+        // Merge across two groups
+        let data = mv.merge_entries(&"vector1", 0..12, combiner)?;
+        assert_eq!(vec!["group2"], data);
+        assert_eq!(3, mv.len());
+        assert_eq!(111 + 222 + 444, mv.get_entry(&"vector1", 11).unwrap().entry.data.data);
+        assert_eq!(0..12, mv.get_entry(&"vector1", 11).unwrap().range);
 
-        // Off the end
-        assert!(mv.insert_entries(vec![
-            (&"vector1", (123, 0, 1000).into()),
-        ]).is_err());
+        // Everything is in the first group now
+        let expected = vec![("vector1", 0), ("vector2", 0)];
+        assert_eq!(expected, mv.get_entry(&"vector1", 0).unwrap().entry.linked);
+        assert_eq!(expected, mv.get_entry(&"vector2", 0).unwrap().entry.linked);
+        assert_eq!("group1", *mv.group_data(&"vector2", 0).unwrap());
 
-        // No entry should be added or removed
-        assert_eq!(4, mv.len());
+        // Merging a single entry works too
+        assert!(mv.merge_entries(&"vector1", 20..24, combiner)?.is_empty());
+        assert_eq!("group3", *mv.group_data(&"vector1", 20).unwrap());
 
-        // Zero length
-        assert!(mv.insert_entries(vec![
-            (&"vector1", (123, 0, 0).into()),
-        ]).is_err());
+        // And removing removes the merged group
+        assert_eq!(2, mv.remove_entries(&"vector2", 0)?.len());
+        assert_eq!(1, mv.len());
 
-        // No entry should be added or removed
-        assert_eq!(4, mv.len());
+        Ok(())
+    }
 
-        // Overlapping each other
-        assert!(mv.insert_entries(vec![
-            (&"vector1", (123, 10, 10).into()),
-            (&"vector1", (123, 20, 10).into()),
-            (&"vector1", (123, 15,  1).into()),
-            (&"vector1", (123, 50, 10).into()),
-        ]).is_err());
+    #[test]
+    fn test_overlapping_vector() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector_with_options("vector2", 100, (), VectorOptions { overlapping: true, ..Default::default() })?;
+        assert!(!mv.vector_options(&"vector1").unwrap().overlapping);
+        assert!(mv.vector_options(&"vector2").unwrap().overlapping);
 
-        // No entry should be added or removed
-        assert_eq!(4, mv.len());
+        // Normal vectors still don't allow overlapping
+        mv.insert_entry(&"vector1", (111, 0, 4).into())?;
+        assert!(mv.insert_entry(&"vector1", (222, 0, 2).into()).is_err());
+        assert!(mv.insert_entry(&"vector1", (222, 2, 4).into()).is_err());
 
-        // Multiple entries that overlap - this ensures that we don't
-        // accidentally remove things from the vector that we shouldn't
+        // A union - two entries at the same index, each linked to something
+        // in the other vector
+        mv.insert_entries(vec![
+            (&"vector2", (222, 0, 4).into()),
+            (&"vector1", (333, 10, 4).into()),
+        ])?;
+        mv.insert_entries(vec![
+            (&"vector2", (444, 0, 2).into()),
+            (&"vector1", (555, 20, 4).into()),
+        ])?;
+
+        // And something that straddles both
+        mv.insert_entry(&"vector2", (666, 1, 8).into())?;
+        assert_eq!(6, mv.len());
+        assert_eq!(3, mv.len_vector(&"vector2").unwrap());
+
+        // Entries in the same group still can't share an index
         assert!(mv.insert_entries(vec![
-            (&"vector1", (123,  0, 10).into()),
-            (&"vector1", (123, 10, 10).into()),
-            (&"vector1", (123, 20, 10).into()),
-            (&"vector2", (123,  0, 10).into()),
+            (&"vector2", (777, 50, 4).into()),
+            (&"vector2", (888, 50, 2).into()),
         ]).is_err());
-
-        // No entry should be added or removed
+        assert_eq!(6, mv.len());
+
+        // Everything covering an index
+        let data: Vec<u32> = mv.get_entries_at(&"vector2", 1).iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![222, 444, 666], data);
+        let data: Vec<u32> = mv.get_entries_at(&"vector2", 3).iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![222, 666], data);
+        assert_eq!(0, mv.get_entries_at(&"vector2", 50).len());
+        assert_eq!(0, mv.get_entries_at(&"badvector", 0).len());
+
+        // A single lookup prefers an entry that starts there
+        assert_eq!(666, mv.get_entry(&"vector2", 1).unwrap().entry.data.data);
+
+        // Links find the right entry, even when another one is at the same
+        // index
+        let group: Vec<u32> = mv.get_entries(&"vector1", 20)?.iter().map(|e| e.unwrap().entry.data.data).collect();
+        assert_eq!(vec![444, 555], group);
+
+        // Moving things around keeps them straight
+        mv.insert_space(&"vector2", 0, 10)?;
+        let group: Vec<u32> = mv.get_entries(&"vector1", 20)?.iter().map(|e| e.unwrap().entry.data.data).collect();
+        assert_eq!(vec![444, 555], group);
+        assert_eq!(3, mv.get_entries_at(&"vector2", 11).len());
+
+        // Removing one side of the union leaves the other alone
+        let removed = mv.remove_entries(&"vector1", 20)?;
+        assert_eq!(444, removed[0].as_ref().unwrap().entry.data.data);
         assert_eq!(4, mv.len());
+        let data: Vec<u32> = mv.get_entries_at(&"vector2", 11).iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![222, 666], data);
+
+        // Removing a range gets everything touching it
+        assert_eq!(2, mv.remove_range(&"vector2", 10..12)?.len());
+        assert_eq!(1, mv.len());
 
         Ok(())
     }
 
     #[test]
-    fn test_get_entry() -> SimpleResult<()> {
+    fn test_vector_alignment() -> SimpleResult<()> {
         let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
-        mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
 
-        // One group of entries
-        mv.insert_entries(vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111, 0,   1).into()),
-            (&"vector1", (222, 5,   5).into()),
-            (&"vector2", (444, 0, 100).into()),
-        ])?;
+        // Zero doesn't make sense for either
+        assert!(mv.create_vector_with_options("bad", 100, (), VectorOptions { alignment: Some(0), ..Default::default() }).is_err());
+        assert!(mv.create_vector_with_options("bad", 100, (), VectorOptions { granularity: Some(0), ..Default::default() }).is_err());
+        assert!(!mv.vector_exists(&"bad"));
+
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector_with_options("vector2", 100, (), VectorOptions {
+            alignment: Some(4),
+            granularity: Some(2),
+            ..Default::default()
+        })?;
+
+        // Misaligned, or a bad size
+        assert_eq!(Err(InsertError::Layout(LayoutError::Misaligned { start: 2, alignment: 4 })), mv.insert_entry(&"vector2", (111, 2, 4).into()));
+        assert_eq!(Err(InsertError::Layout(LayoutError::BadGranularity { size: 3, granularity: 2 })), mv.insert_entry(&"vector2", (111, 4, 3).into()));
+
+        // Other problems aren't layout errors
+        assert!(matches!(mv.insert_entry(&"badvector", (111, 4, 4).into()), Err(InsertError::Other(_))));
+
+        // The whole group fails if one entry does
+        assert_eq!(Err(InsertError::Layout(LayoutError::Misaligned { start: 5, alignment: 4 })), mv.insert_entries(vec![
+            (&"vector1", (111, 1, 1).into()),
+            (&"vector2", (222, 5, 2).into()),
+        ]));
+        assert_eq!(0, mv.len());
 
+        // These are fine
         mv.insert_entries(vec![
-            (&"vector1", (333, 10, 10).into()),
-            (&"vector2", (555, 100, 100).into()),
+            (&"vector1", (111, 1, 1).into()),
+            (&"vector2", (222, 4, 2).into()),
+            (&"vector2", (333, 8, 8).into()),
         ])?;
+        assert_eq!(3, mv.len());
 
-        // Verify that all entries are there
-        assert_eq!(5, mv.len());
+        // Splitting into a misaligned piece fails
+        let splitter = |e: TestEntryType| -> Vec<TestEntryType> {
+            vec![(e.data, 8, 2).into(), (e.data, 10, 6).into()]
+        };
+        assert!(mv.split_entry(&"vector2", 8, &[10], splitter).is_err());
 
-        // Get a couple entries and make sure they're correct
-        assert_eq!(111, mv.get_entry(&"vector1",   0).unwrap().entry.data.data);
-        assert_eq!(222, mv.get_entry(&"vector1",   6).unwrap().entry.data.data);
-        assert_eq!(555, mv.get_entry(&"vector2", 115).unwrap().entry.data.data);
+        // Inserting space that would knock entries out of alignment fails
+        assert!(mv.insert_space(&"vector2", 0, 2).is_err());
+        mv.insert_space(&"vector2", 0, 4)?;
+        assert_eq!(222, mv.get_entry(&"vector2", 8).unwrap().entry.data.data);
 
-        // Get some bad entries, make sure they're errors
-        assert!(mv.get_entry(&"badvector", 123).is_none());
-        assert!(mv.get_entry(&"vector1",  1000).is_none());
-        assert!(mv.get_entry(&"vector1",    50).is_none());
+        // Alignment is by address, if there's a base address
+        mv.create_vector_with_options("vector3", 100, (), VectorOptions { alignment: Some(4), ..Default::default() })?;
+        mv.set_base_address(&"vector3", Some(0x1002))?;
+        assert_eq!(Err(InsertError::Layout(LayoutError::Misaligned { start: 0x1002, alignment: 4 })), mv.insert_entry(&"vector3", (444, 0x1002, 4).into()));
+        mv.insert_entry(&"vector3", (444, 0x1004, 4).into())?;
 
         Ok(())
     }
 
     #[test]
-    fn test_get_entries() -> SimpleResult<()> {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+    fn test_freeze_vector() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
         mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
+        mv.create_vector("vector2", 100)?;
 
-        // One group of entries
+        mv.insert_entries_with(vec![
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector2", (222, 0, 4).into()),
+        ], "group1")?;
         mv.insert_entries(vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111, 0,   1).into()),
-            (&"vector1", (222, 5,   5).into()),
-            (&"vector2", (444, 0, 100).into()),
+            (&"vector2", (333, 10, 4).into()),
+            (&"vector2", (444, 20, 4).into()),
         ])?;
 
-        mv.insert_entries(vec![
-            (&"vector2", (555, 100, 100).into()),
-            (&"vector1", (333, 10, 10).into()),
-        ])?;
+        assert!(mv.freeze_vector(&"badvector").is_err());
+        assert!(mv.thaw_vector(&"badvector").is_err());
+        assert!(!mv.is_frozen(&"badvector"));
 
-        // Verify that all entries are there
-        assert_eq!(5, mv.len());
+        mv.freeze_vector(&"vector1")?;
+        assert!(mv.is_frozen(&"vector1"));
+        assert!(!mv.is_frozen(&"vector2"));
 
-        // Get the first entry at its start
-        let group1 = mv.get_entries(&"vector1", 0)?;
-        assert_eq!(3, group1.len());
+        // Nothing can be added
+        assert_eq!(Err(InsertError::Frozen("vector1")), mv.insert_entry(&"vector1", (555, 50, 4).into()));
+        assert!(mv.insert_entries(vec![
+            (&"vector2", (555, 50, 4).into()),
+            (&"vector1", (666, 50, 4).into()),
+        ]).is_err());
 
-        assert_eq!(111, group1[0].unwrap().entry.data.data);
-        assert_eq!("vector1", group1[0].unwrap().entry.vector);
+        // The group can't be removed, unlinked, or changed, from either side
+        assert!(mv.remove_entries(&"vector1", 0).is_err());
+        assert!(mv.remove_entries(&"vector2", 0).is_err());
+        assert!(mv.unlink_entry(&"vector2", 0).is_err());
+        assert!(mv.set_group_data(&"vector2", 0, Some("group2")).is_err());
+        assert!(mv.group_data_mut(&"vector2", 0).is_err());
+        assert_eq!("group1", *mv.group_data(&"vector2", 0).unwrap());
+
+        // Or tagged or pinned
+        assert!(mv.tag_entry(&"vector1", 0, "tag").is_err());
+        assert!(mv.untag_entry(&"vector1", 0, "tag").is_err());
+        assert!(mv.pin_entry(&"vector1", 0).is_err());
+        assert!(mv.unpin_entry(&"vector1", 0).is_err());
+        assert!(mv.entries_with_tag("tag").is_empty());
+        assert!(!mv.get_entry(&"vector1", 0).unwrap().entry.pinned);
+
+        // Anything touching it in bulk fails without changing anything
+        assert!(mv.remove_range(&"vector2", 0..100).is_err());
+        assert!(mv.destroy_vector_cascade(&"vector1").is_err());
+        assert!(mv.destroy_vector_cascade(&"vector2").is_err());
+        assert_eq!(4, mv.len());
 
-        assert_eq!(222, group1[1].unwrap().entry.data.data);
-        assert_eq!("vector1", group1[1].unwrap().entry.vector);
+        // Groups that don't touch it are fine
+        mv.unlink_entry(&"vector2", 10)?;
+        assert_eq!(1, mv.remove_entries(&"vector2", 20)?.len());
+        assert_eq!(3, mv.len());
 
-        assert_eq!(444, group1[2].unwrap().entry.data.data);
-        assert_eq!("vector2", group1[2].unwrap().entry.vector);
+        // Thaw it, and everything works again
+        mv.thaw_vector(&"vector1")?;
+        assert!(!mv.is_frozen(&"vector1"));
+        assert_eq!(2, mv.remove_entries(&"vector2", 0)?.len());
+        mv.insert_entry(&"vector1", (555, 50, 4).into())?;
+        assert_eq!(2, mv.len());
 
-        // Get the last entry (in the first group) in the middle
-        let group1 = mv.get_entries(&"vector2", 50)?;
-        assert_eq!(3, group1.len());
+        Ok(())
+    }
 
-        assert_eq!(111, group1[0].unwrap().entry.data.data);
-        assert_eq!("vector1", group1[0].unwrap().entry.vector);
+    #[test]
+    fn test_freeze_vector_layout() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
 
-        assert_eq!(222, group1[1].unwrap().entry.data.data);
-        assert_eq!("vector1", group1[1].unwrap().entry.vector);
+        mv.insert_entries(vec![
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector2", (222, 0, 4).into()),
+        ])?;
+        mv.insert_entry(&"vector1", (333, 10, 4).into())?;
+        mv.freeze_vector(&"vector2")?;
 
-        assert_eq!(444, group1[2].unwrap().entry.data.data);
-        assert_eq!("vector2", group1[2].unwrap().entry.vector);
+        // Moving the entries in vector1 would change the links in vector2
+        assert!(mv.insert_space(&"vector1", 0, 10).is_err());
+        assert!(mv.delete_space(&"vector1", 4..10).is_err());
+        assert!(mv.rebase_vector(&"vector1", 10).is_err());
 
-        // Get the second group
-        let group2 = mv.get_entries(&"vector2", 150)?;
-        assert_eq!(2, group2.len());
+        // Same with splitting it
+        let splitter = |e: TestEntryType| -> Vec<TestEntryType> {
+            vec![(e.data, 0, 2).into(), (e.data, 2, 2).into()]
+        };
+        assert!(mv.split_entry(&"vector1", 0, &[2], splitter).is_err());
 
-        assert_eq!(555, group2[0].unwrap().entry.data.data);
-        assert_eq!("vector2", group2[0].unwrap().entry.vector);
+        // The unrelated entry can still be split
+        let splitter = |e: TestEntryType| -> Vec<TestEntryType> {
+            vec![(e.data, 10, 2).into(), (e.data, 12, 2).into()]
+        };
+        mv.split_entry(&"vector1", 10, &[12], splitter)?;
+        assert_eq!(4, mv.len());
 
-        assert_eq!(333, group2[1].unwrap().entry.data.data);
-        assert_eq!("vector1", group2[1].unwrap().entry.vector);
+        // And the base address can't change
+        mv.create_vector("vector3", 100)?;
+        mv.freeze_vector(&"vector3")?;
+        assert!(mv.set_base_address(&"vector3", Some(0x1000)).is_err());
+        assert!(mv.destroy_vector(&"vector3").is_err());
 
-        // Get some bad entries, make sure they're errors
-        assert!(mv.get_entries(&"badvector", 123).is_err());
-        assert!(mv.get_entries(&"vector1",  1000).is_err());
-        assert!(mv.get_entries(&"vector1",    50).is_err());
+        // Forcing it still works
+        assert!(mv.force_destroy_vector(&"vector3").is_some());
 
         Ok(())
     }
 
     #[test]
-    fn test_remove_entries() -> SimpleResult<()> {
+    fn test_pin_entry() -> SimpleResult<()> {
         let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
         mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
-
-        // One group of entries
-        mv.insert_entries(vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111, 0,   1).into()),
-            (&"vector1", (222, 5,   5).into()),
-            (&"vector2", (444, 0, 100).into()),
-        ])?;
+        mv.create_vector("vector2", 100)?;
 
         mv.insert_entries(vec![
-            (&"vector2", (555, 100, 100).into()),
-            (&"vector1", (333,  10,  10).into()),
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector2", (222, 0, 4).into()),
         ])?;
+        mv.insert_entry(&"vector1", (333, 10, 4).into())?;
 
-        // Verify that all entries are there
-        assert_eq!(5, mv.len());
-
-        // Get the first entry at its start
-        let group1 = mv.remove_entries(&"vector1", 0)?;
+        assert!(mv.pin_entry(&"vector1", 50).is_err());
+        assert!(mv.pin_entry(&"badvector", 0).is_err());
+        assert!(mv.unpin_entry(&"vector1", 50).is_err());
 
-        // The group had 3 entries
-        assert_eq!(3, group1.len());
+        mv.pin_entry(&"vector1", 2)?;
+        assert!(mv.get_entry(&"vector1", 0).unwrap().entry.pinned);
+        assert!(!mv.get_entry(&"vector2", 0).unwrap().entry.pinned);
 
-        // Make sure they're actually removed
-        assert_eq!(2, mv.len());
+        // The group can't be removed from either member, and the error says
+        // which entry is to blame
+        let e = mv.remove_entries(&"vector2", 0).unwrap_err();
+        assert!(e.as_str().contains("index 0"));
+        assert!(e.as_str().contains("vector1"));
         assert!(mv.remove_entries(&"vector1", 0).is_err());
+        assert!(mv.remove_range(&"vector2", 0..100).is_err());
+        assert_eq!(3, mv.len());
 
-        assert_eq!(111, group1[0].as_ref().unwrap().entry.data.data);
-        assert_eq!("vector1", group1[0].as_ref().unwrap().entry.vector);
-
-        assert_eq!(222, group1[1].as_ref().unwrap().entry.data.data);
-        assert_eq!("vector1", group1[1].as_ref().unwrap().entry.vector);
-
-        assert_eq!(444, group1[2].as_ref().unwrap().entry.data.data);
-        assert_eq!("vector2", group1[2].as_ref().unwrap().entry.vector);
+        // Splitting keeps the pin
+        let splitter = |e: TestEntryType| -> Vec<TestEntryType> {
+            vec![(e.data, 0, 2).into(), (e.data, 2, 2).into()]
+        };
+        mv.split_entry(&"vector1", 0, &[2], splitter)?;
+        assert!(mv.get_entry(&"vector1", 0).unwrap().entry.pinned);
+        assert!(mv.get_entry(&"vector1", 2).unwrap().entry.pinned);
+        assert!(mv.remove_entries(&"vector2", 0).is_err());
 
-        // Get the second group
-        let group2 = mv.remove_entries(&"vector2", 150)?;
-        assert_eq!(2, group2.len());
+        // Unpinning one piece isn't enough
+        mv.unpin_entry(&"vector1", 0)?;
+        assert!(mv.remove_entries(&"vector2", 0).is_err());
 
-        assert_eq!(555, group2[0].as_ref().unwrap().entry.data.data);
-        assert_eq!("vector2", group2[0].as_ref().unwrap().entry.vector);
+        // Unrelated groups can still be removed
+        assert_eq!(1, mv.remove_range(&"vector1", 10..14)?.len());
 
-        assert_eq!(333, group2[1].as_ref().unwrap().entry.data.data);
-        assert_eq!("vector1", group2[1].as_ref().unwrap().entry.vector);
+        // Forcing it works, and the pins come back with the group
+        assert_eq!(3, mv.force_remove_entries(&"vector2", 0)?.len());
+        assert_eq!(0, mv.len());
 
-        // Get some bad entries, make sure they're errors
-        assert!(mv.remove_entries(&"badvector", 123).is_err());
-        assert!(mv.remove_entries(&"vector1",  1000).is_err());
-        assert!(mv.remove_entries(&"vector1",    50).is_err());
+        mv.insert_entry(&"vector1", (444, 0, 4).into())?;
+        mv.pin_entry(&"vector1", 0)?;
+        let removed = vec![RemovedGroup {
+            entries: mv.force_remove_entries(&"vector1", 0)?.into_iter().flatten().collect(),
+            data: None,
+            options: GroupOptions::default(),
+        }];
+        mv.restore_groups(removed)?;
+        assert!(mv.get_entry(&"vector1", 0).unwrap().entry.pinned);
+        assert!(mv.remove_entries(&"vector1", 0).is_err());
 
         Ok(())
     }
 
     #[test]
-    fn test_unlink_entry() -> SimpleResult<()> {
+    fn test_weak_links() -> SimpleResult<()> {
         let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
-        mv.create_vector(&"vector1", 100)?;
-        mv.create_vector(&"vector2", 200)?;
-
-        // One group of entries
-        mv.insert_entries(vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111, 0,   1).into()),
-            (&"vector1", (222, 5,   5).into()),
-            (&"vector2", (444, 0, 100).into()), // Will be unlinked for test
-        ])?;
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
 
         mv.insert_entries(vec![
-            (&"vector2", (555, 100, 100).into()), // Will be unlinked for test
-            (&"vector1", (333,  10,  10).into()),
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector2", (222, 0, 4).into()),
         ])?;
+        mv.insert_entry(&"vector1", (333, 10, 4).into())?;
+        mv.insert_entry(&"vector2", (444, 10, 4).into())?;
+
+        // Bad entries, or linking to itself
+        assert!(mv.link_weak(&"vector1", 0, &"vector1", 50).is_err());
+        assert!(mv.link_weak(&"badvector", 0, &"vector1", 0).is_err());
+        assert!(mv.link_weak(&"vector1", 0, &"vector1", 2).is_err());
+
+        mv.link_weak(&"vector1", 2, &"vector1", 10)?;
+        mv.link_weak(&"vector1", 11, &"vector2", 10)?;
+
+        // Linking twice does nothing
+        mv.link_weak(&"vector1", 10, &"vector1", 0)?;
+        assert_eq!(vec![("vector1", 10)], mv.get_entry(&"vector1", 0).unwrap().entry.weak);
+
+        // Follow them
+        let data: Vec<u32> = mv.get_weak_links(&"vector1", 10)?.iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![111, 444], data);
+        assert_eq!(0, mv.get_weak_links(&"vector2", 0)?.len());
+        assert!(mv.get_weak_links(&"vector1", 50).is_err());
+
+        // They aren't part of the group
+        assert_eq!(2, mv.get_entries(&"vector1", 0)?.len());
+
+        // Moving things keeps them pointing at the right place
+        mv.insert_space(&"vector1", 5, 10)?;
+        assert_eq!(vec![("vector1", 20)], mv.get_entry(&"vector1", 0).unwrap().entry.weak);
+        assert_eq!(vec![("vector1", 20)], mv.get_entry(&"vector2", 10).unwrap().entry.weak);
+        let data: Vec<u32> = mv.get_weak_links(&"vector1", 20)?.iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![111, 444], data);
+
+        // Removing one end doesn't take the other with it, but does remove
+        // the link
+        let removed = mv.remove_entries(&"vector2", 0)?;
+        assert_eq!(2, removed.len());
+        assert_eq!(2, mv.len());
+        let data: Vec<u32> = mv.get_weak_links(&"vector1", 20)?.iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![444], data);
+
+        // Restoring the group puts the link back
+        mv.restore_groups(vec![RemovedGroup { entries: removed.into_iter().flatten().collect(), data: None, options: GroupOptions::default() }])?;
+        let data: Vec<u32> = mv.get_weak_links(&"vector1", 20)?.iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![444, 111], data);
+
+        // Unlink one
+        assert!(mv.unlink_weak(&"vector1", 0, &"vector2", 10).is_err());
+        mv.unlink_weak(&"vector2", 10, &"vector1", 20)?;
+        assert_eq!(0, mv.get_weak_links(&"vector2", 10)?.len());
+        assert_eq!(1, mv.get_weak_links(&"vector1", 20)?.len());
+
+        // Splitting keeps the link on the first piece
+        let splitter = |e: TestEntryType| -> Vec<TestEntryType> {
+            vec![(e.data, 20, 2).into(), (e.data, 22, 2).into()]
+        };
+        mv.split_entry(&"vector1", 20, &[22], splitter)?;
+        assert_eq!(111, mv.get_weak_links(&"vector1", 20)?[0].entry.data.data);
+        assert_eq!(0, mv.get_weak_links(&"vector1", 22)?.len());
 
-        // Verify that all entries are there
-        assert_eq!(5, mv.len());
+        // Merging moves links to the merged entry
+        mv.link_weak(&"vector1", 22, &"vector2", 10)?;
+        mv.merge_entries(&"vector1", 20..24, |entries| (entries[0].data, 20, 4).into())?;
+        let data: Vec<u32> = mv.get_weak_links(&"vector1", 20)?.iter().map(|e| e.entry.data.data).collect();
+        assert_eq!(vec![111, 444], data);
+        assert_eq!(vec![("vector1", 20)], mv.get_entry(&"vector2", 10).unwrap().entry.weak);
 
-        // Unlink a couple entries
-        mv.unlink_entry(&"vector2",  50)?;
+        // Destroying a vector removes links into it
+        assert!(mv.force_destroy_vector(&"vector2").is_some());
+        assert_eq!(vec![("vector1", 0)], mv.get_entry(&"vector1", 20).unwrap().entry.weak);
 
-        mv.unlink_entry(&"vector2", 150)?;
+        Ok(())
+    }
 
-        // Test error conditions
-        assert!(mv.unlink_entry(&"badvector", 123).is_err());
-        assert!(mv.unlink_entry(&"vector1",  1000).is_err());
-        assert!(mv.unlink_entry(&"vector1",    50).is_err());
+    #[test]
+    fn test_removal_policy() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (111, 0, 4).into()),
+            (&"vector2", (222, 0, 4).into()),
+            (&"vector2", (333, 4, 4).into()),
+        ], Some("removeself"), GroupOptions { removal: RemovalPolicy::RemoveSelf, ..Default::default() })?;
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (444, 10, 4).into()),
+            (&"vector2", (555, 10, 4).into()),
+        ], Some("refuse"), GroupOptions { removal: RemovalPolicy::Refuse, ..Default::default() })?;
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (666, 20, 4).into()),
+            (&"vector1", (777, 24, 4).into()),
+        ], None, GroupOptions { removal: RemovalPolicy::Refuse, ..Default::default() })?;
+        assert_eq!(7, mv.len());
+
+        assert_eq!(RemovalPolicy::RemoveSelf, mv.group_options(&"vector1", 0).unwrap().removal);
+        assert_eq!(RemovalPolicy::Refuse, mv.group_options(&"vector2", 10).unwrap().removal);
+        assert!(mv.group_options(&"vector1", 50).is_none());
+
+        // The default is to cascade
+        mv.insert_entries(vec![
+            (&"vector1", (888, 30, 4).into()),
+            (&"vector2", (999, 30, 4).into()),
+        ])?;
+        assert_eq!(RemovalPolicy::Cascade, mv.group_options(&"vector1", 30).unwrap().removal);
+        assert_eq!(2, mv.remove_entries(&"vector1", 30)?.len());
 
-        // Remove one
-        let removed = mv.remove_entries(&"vector2", 50)?;
+        // Remove-self only takes the one entry, and the rest of the group
+        // keeps its data
+        let (removed, data) = mv.remove_entries_with_data(&"vector2", 0)?;
         assert_eq!(1, removed.len());
+        assert_eq!(222, removed[0].as_ref().unwrap().entry.data.data);
+        assert_eq!(None, data);
+        assert_eq!(vec![("vector1", 0), ("vector2", 4)], mv.get_entry(&"vector1", 0).unwrap().entry.linked);
+        assert_eq!("removeself", *mv.group_data(&"vector2", 4).unwrap());
+
+        // Pins still apply, but only to the entry itself
+        mv.pin_entry(&"vector2", 4)?;
+        assert!(mv.remove_entries(&"vector2", 4).is_err());
+        assert_eq!(1, mv.remove_entries(&"vector1", 0)?.len());
+        assert_eq!(1, mv.force_remove_entries(&"vector2", 4)?.len());
+
+        // Refuse doesn't remove anything while it's linked
+        assert!(mv.remove_entries(&"vector1", 10).is_err());
+        assert!(mv.remove_entries(&"vector2", 10).is_err());
         assert_eq!(4, mv.len());
 
-        // Remove the other
-        let removed = mv.remove_entries(&"vector2", 100)?;
+        // Bulk removal fails if it would split up the group...
+        assert!(mv.remove_range(&"vector1", 0..100).is_err());
+        assert!(mv.destroy_vector_cascade(&"vector2").is_err());
+        assert_eq!(4, mv.len());
+
+        // ...but not if the whole group is going
+        let removed = mv.remove_range(&"vector1", 20..28)?;
         assert_eq!(1, removed.len());
-        assert_eq!(3, mv.len());
+        assert_eq!(2, removed[0].entries.len());
+
+        // And it comes back with the same policy
+        mv.restore_groups(removed)?;
+        assert_eq!(RemovalPolicy::Refuse, mv.group_options(&"vector1", 20).unwrap().removal);
+
+        // A remove-self group that's entirely in the range goes whole, so it
+        // comes back linked
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (123, 40, 4).into()),
+            (&"vector1", (456, 44, 4).into()),
+        ], Some("removeself"), GroupOptions { removal: RemovalPolicy::RemoveSelf, ..Default::default() })?;
+        let removed = mv.remove_range(&"vector1", 40..48)?;
+        assert_eq!(1, removed.len());
+        assert_eq!(2, removed[0].entries.len());
+        assert_eq!(Some("removeself"), removed[0].data);
+        assert_eq!(4, mv.len());
 
-        // Remove the rest of the first group
-        let removed = mv.remove_entries(&"vector1", 0)?;
-        assert_eq!(2, removed.len());
-        assert_eq!(1, mv.len());
+        mv.restore_groups(removed)?;
+        assert_eq!(2, mv.get_entries(&"vector1", 40)?.len());
+        assert_eq!(vec![("vector1", 40), ("vector1", 44)], mv.get_entry(&"vector1", 44).unwrap().entry.linked);
+        assert_eq!("removeself", *mv.group_data(&"vector1", 44).unwrap());
+        assert_eq!(1, mv.remove_range(&"vector1", 40..48)?.len());
+
+        // Once it's unlinked, it can go
+        mv.unlink_entry(&"vector1", 10)?;
+        assert_eq!(1, mv.remove_entries(&"vector1", 10)?.len());
+        assert_eq!(1, mv.remove_entries(&"vector2", 10)?.len());
 
         Ok(())
     }
 
     #[test]
-    #[cfg(feature = "serialize")] // Only test if we enable serialization
-    fn test_serialize() {
-        let mut mv: MultiVector<String, TestEntryType> = MultiVector::new();
-        mv.create_vector("myvector".to_string(), 20).unwrap();
-        mv.insert_entries(vec![
-            (&"myvector".to_string(), (111,  0, 10).into()),
-            (&"myvector".to_string(), (222, 10, 10).into()),
-        ]).unwrap();
-        assert_eq!(2, mv.len());
+    fn test_nested_groups() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
 
-        // Serialize
-        let serialized = ron::ser::to_string(&mv).unwrap();
+        // The parent has to exist
+        assert!(mv.insert_entries_with_options(vec![
+            (&"vector1", (111, 0, 10).into()),
+        ], None, GroupOptions { parent: Some(1000), ..Default::default() }).is_err());
+        assert_eq!(0, mv.len());
 
-        // Deserialize
-        let mut mv: MultiVector<String, TestEntryType> = ron::de::from_str(&serialized).unwrap();
-        assert_eq!(2, mv.len());
+        // outer -> middle -> inner, with a second child of outer
+        mv.insert_entries_with(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector2", (222,  0, 10).into()),
+        ], "outer")?;
+        let outer = mv.get_entry(&"vector1", 0).unwrap().entry.group;
 
-        // Make sure remove still works
-        assert_eq!(2, mv.remove_entries(&"myvector".to_string(), 0).unwrap().len());
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (333, 10, 10).into()),
+            (&"vector1", (444, 20, 10).into()),
+        ], Some("middle"), GroupOptions { parent: Some(outer), ..Default::default() })?;
+        let middle = mv.get_entry(&"vector1", 10).unwrap().entry.group;
+
+        mv.insert_entries_with_options(vec![
+            (&"vector2", (555, 10, 10).into()),
+        ], Some("inner"), GroupOptions { parent: Some(middle), ..Default::default() })?;
+        let inner = mv.get_entry(&"vector2", 10).unwrap().entry.group;
+
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (666, 50, 10).into()),
+        ], Some("other"), GroupOptions { parent: Some(outer), ..Default::default() })?;
+        let other = mv.get_entry(&"vector1", 50).unwrap().entry.group;
+
+        assert_eq!(None, mv.parent_group(outer));
+        assert_eq!(Some(outer), mv.parent_group(middle));
+        assert_eq!(Some(middle), mv.parent_group(inner));
+        assert_eq!(vec![middle, other], mv.child_groups(outer));
+        assert_eq!(Vec::<usize>::new(), mv.child_groups(inner));
+        assert_eq!(vec![outer, middle, inner, other], mv.group_tree(outer));
+        assert_eq!(Vec::<usize>::new(), mv.group_tree(1000));
+        assert_eq!(vec![333, 444], mv.group_entries(middle).iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+
+        // A pinned entry anywhere in the tree blocks removing the top
+        mv.pin_entry(&"vector2", 10)?;
+        assert!(mv.remove_entries(&"vector1", 0).is_err());
+        assert!(mv.remove_range(&"vector2", 0..5).is_err());
+        assert_eq!(6, mv.len());
+        mv.unpin_entry(&"vector2", 10)?;
+
+        // Removing a range removes the nested groups separately, so they can
+        // be restored with their nesting intact
+        let removed = mv.remove_range(&"vector2", 0..5)?;
+        assert_eq!(4, removed.len());
+        assert_eq!(Some("outer"), removed[0].data);
+        assert_eq!(Some("middle"), removed[1].data);
+        assert_eq!(Some("inner"), removed[2].data);
+        assert_eq!(Some("other"), removed[3].data);
         assert_eq!(0, mv.len());
+
+        mv.restore_groups(removed)?;
+        assert_eq!(6, mv.len());
+        let outer = mv.get_entry(&"vector1", 0).unwrap().entry.group;
+        let middle = mv.get_entry(&"vector1", 10).unwrap().entry.group;
+        let inner = mv.get_entry(&"vector2", 10).unwrap().entry.group;
+        let other = mv.get_entry(&"vector1", 50).unwrap().entry.group;
+        assert_eq!(vec![outer, middle, inner, other], mv.group_tree(outer));
+
+        // Merging a nested group into another moves its children over
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (777, 30, 10).into()),
+        ], None, GroupOptions { parent: Some(outer), ..Default::default() })?;
+        let merged = mv.get_entry(&"vector1", 30).unwrap().entry.group;
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (888, 60, 10).into()),
+        ], None, GroupOptions { parent: Some(merged), ..Default::default() })?;
+        let grandchild = mv.get_entry(&"vector1", 60).unwrap().entry.group;
+
+        mv.merge_entries(&"vector1", 20..40, |_| (777, 20, 20).into())?;
+        assert_eq!(None, mv.parent_group(merged));
+        assert_eq!(vec![inner, grandchild], mv.child_groups(middle));
+        assert_eq!(vec![middle, other], mv.child_groups(outer));
+        assert_eq!(7, mv.len());
+
+        // Removing the middle only takes what's inside it
+        assert_eq!(4, mv.remove_entries(&"vector1", 10)?.len());
+        assert_eq!(3, mv.len());
+        assert_eq!(vec![other], mv.child_groups(outer));
+
+        // Removing the top takes everything
+        let (removed, data) = mv.remove_entries_with_data(&"vector2", 0)?;
+        assert_eq!(3, removed.len());
+        assert_eq!(Some("outer"), data);
+        assert_eq!(0, mv.len());
+
+        Ok(())
     }
 
     #[test]
-    fn test_clone() {
-        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
-        mv.create_vector("myvector", 20).unwrap();
-        mv.insert_entries(vec![
-            (&"myvector", (111,  0, 10).into()),
-            (&"myvector", (222, 10, 10).into()),
-        ]).unwrap();
-        assert_eq!(2, mv.len());
-
-        // Clone
-        let mut mv2 = mv.clone();
-        assert_eq!(2, mv2.len());
+    fn test_split_group() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType, (), &str> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
+
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 10).into()),
+            (&"vector2", (333,  0, 10).into()),
+            (&"vector2", (444, 10, 10).into()),
+        ], Some("data"), GroupOptions { removal: RemovalPolicy::Refuse, ..Default::default() })?;
+        mv.insert_entry(&"vector1", (555, 50, 10).into())?;
+        let group = mv.get_entry(&"vector1", 0).unwrap().entry.group;
+
+        // Bad splits don't change anything
+        assert!(mv.split_group(&"vector1", 0, &[]).is_err());
+        assert!(mv.split_group(&"vector1", 0, &[("vector1", 55)]).is_err());
+        assert!(mv.split_group(&"vector1", 0, &[("vector2", 5), ("vector2", 0)]).is_err());
+        assert!(mv.split_group(&"vector1", 0, &[("vector1", 0), ("vector1", 10), ("vector2", 0), ("vector2", 10)]).is_err());
+        assert!(mv.split_group(&"vector1", 30, &[("vector2", 0)]).is_err());
+        assert_eq!(4, mv.get_entry(&"vector1", 0).unwrap().entry.linked.len());
+
+        // Frozen vectors can't be split
+        mv.freeze_vector(&"vector2")?;
+        assert!(mv.split_group(&"vector1", 0, &[("vector2", 0)]).is_err());
+        mv.thaw_vector(&"vector2")?;
+
+        // Split off the entries in vector2 (in a different order than they
+        // were inserted)
+        let new_group = mv.split_group(&"vector1", 5, &[("vector2", 15), ("vector2", 5)])?;
+        assert_ne!(group, new_group);
+
+        assert_eq!(vec![("vector1", 0), ("vector1", 10)], mv.get_entry(&"vector1", 0).unwrap().entry.linked);
+        assert_eq!(vec![("vector1", 0), ("vector1", 10)], mv.get_entry(&"vector1", 10).unwrap().entry.linked);
+        assert_eq!(vec![("vector2", 0), ("vector2", 10)], mv.get_entry(&"vector2", 0).unwrap().entry.linked);
+        assert_eq!(vec![("vector2", 0), ("vector2", 10)], mv.get_entry(&"vector2", 10).unwrap().entry.linked);
+        assert_eq!(new_group, mv.get_entry(&"vector2", 10).unwrap().entry.group);
+
+        // The data stays, the options are copied
+        assert_eq!(Some(&"data"), mv.group_data(&"vector1", 0));
+        assert_eq!(None, mv.group_data(&"vector2", 0));
+        assert_eq!(RemovalPolicy::Refuse, mv.group_options(&"vector2", 0).unwrap().removal);
+
+        // Each group goes away by itself
+        mv.unlink_entry(&"vector2", 0)?;
+        assert_eq!(1, mv.remove_entries(&"vector2", 10)?.len());
+        assert_eq!(4, mv.len());
 
-        // Make sure remove still works
-        assert_eq!(2, mv2.remove_entries(&"myvector", 0).unwrap().len());
-        assert_eq!(0, mv2.len());
+        // Both halves of a nested group stay nested in its parent
+        mv.insert_entry(&"vector1", (666, 70, 5).into())?;
+        let parent = mv.get_entry(&"vector1", 70).unwrap().entry.group;
+        mv.insert_entries_with_options(vec![
+            (&"vector1", (777, 80, 5).into()),
+            (&"vector1", (888, 90, 5).into()),
+        ], None, GroupOptions { parent: Some(parent), ..Default::default() })?;
+        let child = mv.get_entry(&"vector1", 80).unwrap().entry.group;
+        let piece = mv.split_group(&"vector1", 80, &[("vector1", 90)])?;
+        assert_eq!(vec![child, piece], mv.child_groups(parent));
+
+        // So removing the parent takes both
+        assert_eq!(3, mv.remove_entries(&"vector1", 70)?.len());
+        assert_eq!(4, mv.len());
 
-        // Make sure the original is untouched
-        assert_eq!(2, mv.len());
+        Ok(())
     }
 
     #[test]
-    fn test_iterator() -> SimpleResult<()> {
+    fn test_tags() -> SimpleResult<()> {
         let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
         mv.create_vector("vector1", 100)?;
-        mv.create_vector("vector2", 200)?;
+        mv.create_vector_with_options("vector2", 100, (), VectorOptions { overlapping: true, ..Default::default() })?;
 
-        let entries = vec![
-            // (vector_name, ( data, index, length ) )
-            (&"vector1", (111,  0,    1).into()),
-            (&"vector1", (222,  5,    5).into()),
-            (&"vector1", (333, 10,   10).into()),
+        mv.insert_entries(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 10).into()),
+        ])?;
+        mv.insert_entry(&"vector1", (333, 20, 10).into())?;
+        mv.insert_entry(&"vector2", (444,  0, 10).into())?;
+        mv.insert_entry(&"vector2", (555,  0, 20).into())?;
+
+        // Tagging something that isn't there fails
+        assert!(mv.tag_entry(&"vector1", 50, "auto").is_err());
+        assert!(mv.tag_entry(&"fakevector", 0, "auto").is_err());
+        assert!(mv.untag_entry(&"vector1", 50, "auto").is_err());
+
+        mv.tag_entry(&"vector1", 5, "auto")?;
+        mv.tag_entry(&"vector1", 5, "auto")?;
+        mv.tag_entry(&"vector1", 5, "user")?;
+        mv.tag_entry(&"vector1", 25, "auto")?;
+        assert_eq!(vec!["auto".to_string(), "user".to_string()], mv.get_entry(&"vector1", 0).unwrap().entry.tags);
+        assert_eq!(vec![111, 333], mv.entries_with_tag("auto").iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(0, mv.entries_with_tag("suspicious").len());
+
+        // Two entries at the same index are tracked separately
+        mv.tag_entry(&"vector2", 0, "overlap")?;
+        mv.tag_entry(&"vector2", 15, "overlap")?;
+        assert_eq!(2, mv.entries_with_tag("overlap").len());
+        mv.untag_entry(&"vector2", 0, "overlap")?;
+        assert_eq!(vec![555], mv.entries_with_tag("overlap").iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+
+        // Moving entries moves the tags
+        mv.insert_space(&"vector1", 0, 5)?;
+        assert_eq!(vec![5, 25], mv.entries_with_tag("auto").iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        // Splitting and merging
+        mv.split_entry(&"vector1", 25, &[30], |e| vec![(e.data, 25, 5).into(), (e.data, 30, 5).into()])?;
+        assert_eq!(vec![5, 25, 30], mv.entries_with_tag("auto").iter().map(|e| e.range.start).collect::<Vec<usize>>());
+        mv.merge_entries(&"vector1", 25..35, |_| (333, 25, 10).into())?;
+        assert_eq!(vec![5, 25], mv.entries_with_tag("auto").iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        // Removing takes them away, and restoring brings them back
+        let removed = mv.remove_entries(&"vector1", 15)?;
+        assert_eq!(vec!["auto".to_string(), "user".to_string()], removed[0].as_ref().unwrap().entry.tags);
+        assert_eq!(0, mv.entries_with_tag("user").len());
+        assert_eq!(1, mv.entries_with_tag("auto").len());
+
+        let removed = mv.remove_range(&"vector1", 0..100)?;
+        assert_eq!(0, mv.entries_with_tag("auto").len());
+        mv.restore_groups(removed)?;
+        assert_eq!(vec![25], mv.entries_with_tag("auto").iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        // Untagging the last one cleans up the index
+        mv.untag_entry(&"vector1", 25, "auto")?;
+        assert_eq!(0, mv.entries_with_tag("auto").len());
+        assert!(!mv.vectors.get(&"vector1").unwrap().entries.tags.keys.contains_key("auto"));
 
-            (&"vector2", (444, 0,   100).into()),
-            (&"vector2", (555, 100, 100).into()),
-        ];
+        Ok(())
+    }
 
-        // Insert the entries
-        mv.insert_entries(entries)?;
+    #[test]
+    #[cfg(feature = "serialize")] // Only test if we enable serialization
+    fn test_serialize_tags() {
+        let mut mv: MultiVector<String, TestEntryType> = MultiVector::new();
+        mv.create_vector("myvector".to_string(), 20).unwrap();
+        mv.insert_entry(&"myvector".to_string(), (111,  0, 10).into()).unwrap();
+        mv.insert_entry(&"myvector".to_string(), (222, 10, 10).into()).unwrap();
+        mv.tag_entry(&"myvector".to_string(), 10, "user").unwrap();
 
-        let mut i = mv.into_iter();
-        assert!(i.next().is_some());
-        assert!(i.next().is_some());
-        assert!(i.next().is_some());
-        assert!(i.next().is_some());
-        assert!(i.next().is_some());
-        assert!(i.next().is_none());
+        let serialized = ron::ser::to_string(&mv).unwrap();
+        let mut mv: MultiVector<String, TestEntryType> = ron::de::from_str(&serialized).unwrap();
 
-        let mut i2 = mv.into_iter();
-        assert!(i2.next().is_some());
-        assert!(i2.next().is_some());
-        assert!(i2.next().is_some());
-        assert!(i2.next().is_some());
-        assert!(i2.next().is_some());
-        assert!(i2.next().is_none());
+        assert_eq!(1, mv.entries_with_tag("user").len());
+        assert_eq!(222, mv.entries_with_tag("user")[0].entry.data.data);
+
+        // The index still works after deserializing
+        mv.remove_entries(&"myvector".to_string(), 10).unwrap();
+        assert_eq!(0, mv.entries_with_tag("user").len());
+    }
+
+    #[test]
+    fn test_indexes() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+
+        mv.insert_entries(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 10).into()),
+        ])?;
+        mv.insert_entry(&"vector1", (333, 20, 10).into())?;
+
+        // Nothing's registered yet
+        assert!(mv.entries_with_key("parity", &"odd").is_err());
+
+        // Index by odd/even, setting aside anything over 500
+        mv.register_index("parity", |e: &TestEntryType| match e.data {
+            d if d > 500 => "big",
+            d if d % 2 == 0 => "even",
+            _ => "odd",
+        });
+        assert_eq!(vec![111, 333], mv.entries_with_key("parity", &"odd")?.iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(vec![222], mv.entries_with_key("parity", &"even")?.iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(0, mv.entries_with_key("parity", &"neither")?.len());
+
+        // Vectors created later are indexed too
+        mv.create_vector("vector2", 100)?;
+        mv.insert_entry(&"vector2", (444, 0, 10).into())?;
+        mv.insert_entry(&"vector2", (600, 50, 10).into())?;
+        assert_eq!(2, mv.entries_with_key("parity", &"even")?.len());
+
+        // Replacing changes the key
+        assert!(mv.replace_entry(&"vector1", 20, (335, 20, 5).into()).is_err());
+        assert_eq!(333, mv.replace_entry(&"vector1", 25, (334, 20, 10).into())?.data);
+        assert_eq!(334, mv.get_entry(&"vector1", 20).unwrap().entry.data.data);
+        assert_eq!(1, mv.entries_with_key("parity", &"odd")?.len());
+        assert_eq!(3, mv.entries_with_key("parity", &"even")?.len());
+
+        // ...including to one that's set aside
+        mv.replace_entry(&"vector2", 0, (700, 0, 10).into())?;
+        assert_eq!(2, mv.entries_with_key("parity", &"even")?.len());
+        assert_eq!(2, mv.entries_with_key("parity", &"big")?.len());
+
+        // Frozen vectors can't be changed
+        mv.freeze_vector(&"vector1")?;
+        assert!(mv.replace_entry(&"vector1", 0, (112, 0, 10).into()).is_err());
+        mv.thaw_vector(&"vector1")?;
+
+        // Moving, splitting, and removing keep it up to date
+        mv.insert_space(&"vector1", 0, 5)?;
+        assert_eq!(vec![5], mv.entries_with_key("parity", &"odd")?.iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        mv.split_entry(&"vector1", 5, &[10], |e| vec![(e.data, 5, 5).into(), (e.data + 1, 10, 5).into()])?;
+        assert_eq!(vec![5], mv.entries_with_key("parity", &"odd")?.iter().map(|e| e.range.start).collect::<Vec<usize>>());
+        assert_eq!(vec![10, 15, 25], mv.entries_with_key("parity", &"even")?.iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        mv.remove_entries(&"vector1", 5)?;
+        assert_eq!(0, mv.entries_with_key("parity", &"odd")?.len());
+        assert_eq!(vec![25], mv.entries_with_key("parity", &"even")?.iter().map(|e| e.range.start).collect::<Vec<usize>>());
+
+        // Registering again with the same name replaces it, even with a
+        // different type of key
+        mv.register_index("parity", |e: &TestEntryType| e.data);
+        assert_eq!(1, mv.entries_with_key("parity", &334u32)?.len());
+        assert_eq!(1, mv.entries_with_key("parity", &600u32)?.len());
+        assert!(mv.entries_with_key("parity", &"even").is_err());
+
+        assert!(mv.unregister_index("parity"));
+        assert!(!mv.unregister_index("parity"));
+        assert!(mv.entries_with_key("parity", &334u32).is_err());
+
+        // A vector that's restored is indexed with whatever's registered now
+        mv.register_index("stale", |e: &TestEntryType| e.data);
+        let destroyed = mv.destroy_vector_cascade(&"vector2")?;
+        mv.unregister_index("stale");
+        mv.register_index("key", |e: &TestEntryType| e.data / 100);
+        mv.restore_vector(destroyed)?;
+        assert_eq!(vec![700], mv.entries_with_key("key", &7u32)?.iter().map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert!(mv.entries_with_key("stale", &700u32).is_err());
 
         Ok(())
     }
 
     #[test]
-    fn test_non_string_keys() -> SimpleResult<()>{
-        let mut mv: MultiVector<(u8, &str), TestEntryType> = MultiVector::new();
-        mv.create_vector((1, "vector1"), 100)?;
-        mv.create_vector((2, "vector1"), 200)?;
-        mv.create_vector((1, "vector2"), 300)?;
-        mv.create_vector((2, "vector2"), 300)?;
+    #[cfg(feature = "serialize")] // Only test if we enable serialization
+    fn test_serialize_indexes() {
+        let mut mv: MultiVector<String, TestEntryType> = MultiVector::new();
+        mv.create_vector("myvector".to_string(), 20).unwrap();
+        mv.insert_entry(&"myvector".to_string(), (111,  0, 10).into()).unwrap();
+        mv.insert_entry(&"myvector".to_string(), (222, 10, 10).into()).unwrap();
+        mv.register_index("data", |e: &TestEntryType| e.data);
 
-        let entries = vec![
-            // (vector_name, ( data, index, length ) )
-            (&(1, "vector1"), (111,  0,  1).into()),
-            (&(1, "vector1"), (222,  5,  5).into()),
-            (&(1, "vector1"), (333, 10, 10).into()),
+        let serialized = ron::ser::to_string(&mv).unwrap();
+        let mut mv: MultiVector<String, TestEntryType> = ron::de::from_str(&serialized).unwrap();
 
-            (&(2, "vector1"), (444,  0,  1).into()),
-            (&(2, "vector1"), (555,  5,  5).into()),
+        // The index has to be registered again...
+        assert!(mv.entries_with_key("data", &222u32).is_err());
 
-            (&(1, "vector2"), (777,  0,  1).into()),
-        ];
+        // ...and then it's rebuilt
+        mv.register_index("data", |e: &TestEntryType| e.data);
+        assert_eq!(10, mv.entries_with_key("data", &222u32).unwrap()[0].range.start);
 
-        // They are empty before
-        assert_eq!(0, mv.len_vector(&(1, "vector1")).unwrap());
-        assert_eq!(0, mv.len_vector(&(2, "vector1")).unwrap());
-        assert_eq!(0, mv.len_vector(&(1, "vector2")).unwrap());
-        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+        mv.remove_entries(&"myvector".to_string(), 10).unwrap();
+        assert_eq!(0, mv.entries_with_key("data", &222u32).unwrap().len());
+    }
 
-        // Insert the entries
-        mv.insert_entries(entries)?;
+    #[test]
+    fn test_find() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 100)?;
+        mv.create_vector("vector2", 100)?;
 
-        // They are populated after
-        assert_eq!(3, mv.len_vector(&(1, "vector1")).unwrap());
-        assert_eq!(2, mv.len_vector(&(2, "vector1")).unwrap());
-        assert_eq!(1, mv.len_vector(&(1, "vector2")).unwrap());
-        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+        mv.insert_entries(vec![
+            (&"vector1", (111,  0, 10).into()),
+            (&"vector1", (222, 10, 20).into()),
+        ])?;
+        mv.insert_entries(vec![
+            (&"vector1", (333, 50, 20).into()),
+            (&"vector2", (444,  0, 20).into()),
+        ])?;
+        mv.insert_entry(&"vector2", (555, 50, 5).into())?;
+
+        // Everything, everywhere
+        let mut found: Vec<u32> = mv.find(|e| e.data.size >= 20).map(|e| e.entry.data.data).collect();
+        found.sort_unstable();
+        assert_eq!(vec![222, 333, 444], found);
+
+        // The predicate can borrow things
+        let wanted = [111, 555];
+        let mut found: Vec<u32> = mv.find(|e| wanted.contains(&e.data.data)).map(|e| e.entry.data.data).collect();
+        found.sort_unstable();
+        assert_eq!(vec![111, 555], found);
+
+        // Only some vectors (in the order given), and only part of them
+        assert_eq!(vec![444, 555, 111, 222, 333], mv.find_in(&["vector2", "fakevector", "vector1"], None, |_| true).map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(vec![222, 333], mv.find_in(&["vector1"], Some(25..55), |_| true).map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(vec![333], mv.find_in(&["vector1"], Some(25..55), |e| e.data.data > 300).map(|e| e.entry.data.data).collect::<Vec<u32>>());
+        assert_eq!(0, mv.find_in(&[], None, |_| true).count());
+
+        // The predicate is only called as the results are used
+        let calls = std::cell::Cell::new(0);
+        let mut found = mv.find_in(&["vector1"], None, |_| { calls.set(calls.get() + 1); true });
+        assert_eq!(0, calls.get());
+        assert_eq!(111, found.next().unwrap().entry.data.data);
+        assert_eq!(1, calls.get());
+
+        // Groups are only looked at once
+        let mut found: Vec<Vec<u32>> = mv.find_groups(|_| true).map(|g| g.iter().map(|e| e.entry.data.data).collect()).collect();
+        found.sort_unstable();
+        assert_eq!(vec![vec![111, 222], vec![333, 444], vec![555]], found);
+
+        let found: Vec<Vec<u32>> = mv.find_groups(|g| g.iter().any(|e| e.entry.vector != g[0].entry.vector)).map(|g| g.iter().map(|e| e.entry.data.data).collect()).collect();
+        assert_eq!(vec![vec![333, 444]], found);
+
+        // A group only needs one entry in the range, but gets all of them
+        let found: Vec<Vec<u32>> = mv.find_groups_in(&["vector2"], Some(0..10), |_| true).map(|g| g.iter().map(|e| e.entry.data.data).collect()).collect();
+        assert_eq!(vec![vec![333, 444]], found);
+        assert_eq!(0, mv.find_groups_in(&["vector2"], Some(0..10), |g| g.len() == 1).count());
 
-        // Unlink and remove a single entry
-        assert!(mv.unlink_entry(&(1, "vector1"), 0).is_ok());
-        assert!(mv.remove_entries(&(1, "vector1"), 0).is_ok());
+        Ok(())
+    }
 
-        // Make sure just the one went away
-        assert_eq!(2, mv.len_vector(&(1, "vector1")).unwrap());
-        assert_eq!(2, mv.len_vector(&(2, "vector1")).unwrap());
-        assert_eq!(1, mv.len_vector(&(1, "vector2")).unwrap());
-        assert_eq!(0, mv.len_vector(&(2, "vector2")).unwrap());
+    #[test]
+    fn test_query() -> SimpleResult<()> {
+        let mut mv: MultiVector<&str, TestEntryType> = MultiVector::new();
+        mv.create_vector("vector1", 1000)?;
+        mv.create_vector_with_options("vector2", 1000, (), VectorOptions { overlapping: true, ..Default::default() })?;
 
-        // Remove the entire rest of the group
-        assert!(mv.remove_entries(&(1, "vector2"), 0).is_ok());
+        mv.insert_entries(vec![
+            (&"vector1", (111,   0, 10).into()),
+            (&"vector1", (222,  10, 10).into()),
+            (&"vector2", (333,   0, 50).into()),
+        ])?;
+        mv.insert_entry(&"vector1", (444,  40, 20).into())?;
+        mv.insert_entry(&"vector1", (555, 500, 10).into())?;
+        mv.insert_entry(&"vector2", (666,  20, 10).into())?;
+        mv.insert_entry(&"vector2", (777, 990, 10).into())?;
+
+        let data = |query: Query<&str, TestEntryType, (), ()>| query.into_iter().map(|e| e.entry.data.data).collect::<Vec<u32>>();
+
+        // No filters means everything
+        assert_eq!(7, mv.query().into_iter().count());
+
+        // Vectors, in order
+        assert_eq!(vec![333, 666, 777, 111, 222, 444, 555], data(mv.query().vectors(&["vector2", "fakevector", "vector1"])));
+
+        // Small ranges step through the vector, starting inside an entry and
+        // running off the end
+        assert_eq!(vec![222, 444], data(mv.query().vectors(&["vector1"]).range(15..45)));
+        assert_eq!(vec![333, 666], data(mv.query().vectors(&["vector2"]).range(25..26)));
+        assert_eq!(vec![777], data(mv.query().vectors(&["vector2"]).range(995..2000)));
+        assert_eq!(0, mv.query().range(60..70).into_iter().count());
+
+        // Big ranges look at every entry instead, with the same results
+        assert_eq!(vec![111, 222, 444], data(mv.query().vectors(&["vector1"]).range(0..100)));
+        assert_eq!(vec![333, 666], data(mv.query().vectors(&["vector2"]).range(0..100)));
+
+        // Group sizes
+        assert_eq!(vec![111, 222], data(mv.query().vectors(&["vector1"]).group_size(2..)));
+        assert_eq!(vec![444, 555], data(mv.query().vectors(&["vector1"]).group_size(..=1)));
+        assert_eq!(vec![333], data(mv.query().vectors(&["vector2"]).group_size(3..=3)));
+        assert_eq!(0, mv.query().group_size(4..).into_iter().count());
+
+        // Data predicates all have to match
+        assert_eq!(vec![111, 555, 333, 777], data(mv.query().vectors(&["vector1", "vector2"]).data(|e| e.data % 2 == 1)));
+        assert_eq!(vec![333, 777], data(mv.query().vectors(&["vector1", "vector2"]).data(|e| e.data % 2 == 1).data(|e| e.data > 300 && e.data != 555)));
+
+        // Everything together, with the cheap filters first - the predicate
+        // only sees what's left
+        let calls = std::cell::Cell::new(0);
+        let found = data(mv.query()
+            .data(|e| { calls.set(calls.get() + 1); e.data > 100 })
+            .vectors(&["vector1", "vector2"])
+            .range(0..30)
+            .group_size(2..));
+        assert_eq!(vec![111, 222, 333], found);
+        assert_eq!(3, calls.get());
 
         Ok(())
     }